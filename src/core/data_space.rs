@@ -0,0 +1,189 @@
+use crate::core::error::OperationError;
+
+/// Largest number of bytes the data space can hold, so every address fits in a cell.
+const DATA_SPACE_CAPACITY: usize = i16::MAX as usize;
+
+/// The memory that Forth code can address, e.g. to store strings.
+///
+/// Addresses are byte offsets into the data space, starting at 0, and are pushed on the
+/// stack as regular cells. Strings are referred to by their address and length.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataSpace {
+    bytes: Vec<u8>,
+    /// Address and size of the buffer reused by `store_transient`.
+    transient: (i16, i16),
+}
+
+impl DataSpace {
+    /// Creates an empty data space.
+    pub fn new() -> Self {
+        DataSpace::default()
+    }
+
+    /// Creates a data space that holds `bytes`, e.g. when restoring an image.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        DataSpace {
+            bytes,
+            transient: (0, 0),
+        }
+    }
+
+    /// Returns every byte allotted so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the number of bytes allotted so far.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Checks if nothing has been allotted yet.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Appends `bytes` to the data space.
+    ///
+    /// # Returns
+    /// * The address of the first byte.
+    /// * `Err(OperationError::DataSpaceOverflow)` if the data space has no room for them.
+    pub fn allot(&mut self, bytes: &[u8]) -> Result<i16, OperationError> {
+        if self.bytes.len() + bytes.len() > DATA_SPACE_CAPACITY {
+            return Err(OperationError::DataSpaceOverflow);
+        }
+        let address = self.bytes.len() as i16;
+        self.bytes.extend_from_slice(bytes);
+        Ok(address)
+    }
+
+    /// Appends the UTF-8 bytes of `text` to the data space.
+    ///
+    /// # Returns
+    /// * The address and the length of the stored string.
+    /// * `Err(OperationError::DataSpaceOverflow)` if the data space has no room for it.
+    pub fn store_string(&mut self, text: &str) -> Result<(i16, i16), OperationError> {
+        let address = self.allot(text.as_bytes())?;
+        Ok((address, text.len() as i16))
+    }
+
+    /// Stores `text` in a transient buffer that every call reuses, so strings produced over
+    /// and over (e.g. by `getenv`) do not fill the data space. The buffer only grows when
+    /// `text` does not fit in it, and the string is only valid until the next call.
+    ///
+    /// # Returns
+    /// * The address and the length of the stored string.
+    /// * `Err(OperationError::DataSpaceOverflow)` if the buffer has to grow and the data
+    ///   space has no room for it.
+    pub fn store_transient(&mut self, text: &str) -> Result<(i16, i16), OperationError> {
+        let (address, size) = self.transient;
+        if text.len() > size as usize {
+            self.transient = self.store_string(text)?;
+            return Ok(self.transient);
+        }
+        let start = address as usize;
+        self.bytes[start..start + text.len()].copy_from_slice(text.as_bytes());
+        Ok((address, text.len() as i16))
+    }
+
+    /// Returns the `len` bytes that start at `address`.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::InvalidAddress)` if any of them is outside the data space.
+    pub fn read(&self, address: i16, len: i16) -> Result<&[u8], OperationError> {
+        if address < 0 || len < 0 {
+            return Err(OperationError::InvalidAddress);
+        }
+        let start = address as usize;
+        self.bytes
+            .get(start..start + len as usize)
+            .ok_or(OperationError::InvalidAddress)
+    }
+
+    /// Returns the string of `len` bytes that starts at `address`, replacing the
+    /// sequences that are not valid UTF-8.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::InvalidAddress)` if it is outside the data space.
+    pub fn read_string(&self, address: i16, len: i16) -> Result<String, OperationError> {
+        Ok(String::from_utf8_lossy(self.read(address, len)?).into_owned())
+    }
+
+    /// Returns the byte at `address`.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::InvalidAddress)` if it is outside the data space.
+    pub fn fetch(&self, address: i16) -> Result<u8, OperationError> {
+        Ok(self.read(address, 1)?[0])
+    }
+
+    /// Replaces the byte at `address` with `value`.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::InvalidAddress)` if it is outside the data space.
+    pub fn store(&mut self, address: i16, value: u8) -> Result<(), OperationError> {
+        let byte = usize::try_from(address)
+            .ok()
+            .and_then(|address| self.bytes.get_mut(address))
+            .ok_or(OperationError::InvalidAddress)?;
+        *byte = value;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_read_strings() {
+        let mut data_space = DataSpace::new();
+        assert_eq!(data_space.store_string("hello"), Ok((0, 5)));
+        assert_eq!(data_space.store_string("forth"), Ok((5, 5)));
+        assert_eq!(data_space.read_string(5, 5), Ok("forth".to_string()));
+        assert_eq!(data_space.read(3, 4), Ok("lofo".as_bytes()));
+    }
+
+    #[test]
+    fn test_fetch_and_store_bytes() {
+        let mut data_space = DataSpace::from_bytes(b"abc".to_vec());
+        data_space.store(1, b'x').unwrap();
+        assert_eq!(data_space.fetch(1), Ok(b'x'));
+        assert_eq!(data_space.as_bytes(), b"axc");
+        assert_eq!(data_space.store(3, 0), Err(OperationError::InvalidAddress));
+        assert_eq!(data_space.fetch(-1), Err(OperationError::InvalidAddress));
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        let mut data_space = DataSpace::new();
+        data_space.store_string("abc").unwrap();
+        assert_eq!(data_space.read(2, 2), Err(OperationError::InvalidAddress));
+        assert_eq!(data_space.read(-1, 1), Err(OperationError::InvalidAddress));
+        assert_eq!(data_space.read(0, -1), Err(OperationError::InvalidAddress));
+    }
+
+    #[test]
+    fn test_store_transient_reuses_its_buffer() {
+        let mut data_space = DataSpace::new();
+        assert_eq!(data_space.store_transient("forth"), Ok((0, 5)));
+        assert_eq!(data_space.store_transient("abc"), Ok((0, 3)));
+        assert_eq!(data_space.read_string(0, 3), Ok("abc".to_string()));
+        assert_eq!(data_space.len(), 5);
+
+        data_space.store_string("x").unwrap();
+        assert_eq!(data_space.store_transient("longer"), Ok((6, 6)));
+        assert_eq!(data_space.read_string(5, 1), Ok("x".to_string()));
+        assert_eq!(data_space.len(), 12);
+    }
+
+    #[test]
+    fn test_allot_overflow() {
+        let mut data_space = DataSpace::new();
+        data_space.allot(&vec![0; DATA_SPACE_CAPACITY]).unwrap();
+        assert_eq!(
+            data_space.allot(&[1]),
+            Err(OperationError::DataSpaceOverflow)
+        );
+    }
+}
@@ -1,7 +1,8 @@
+use crate::utils::file_manager::SourcePosition;
 use std::fmt;
 
 /// Represents possible errors that can occur during operation execution.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum OperationError {
     /// The stack does not contain enough elements to perform the operation.
     StackUnderflow,
@@ -11,10 +12,26 @@ pub enum OperationError {
     DivisionByZero,
     /// An invalid word was encountered.
     InvalidWord,
-    /// Failed to write the stack to the file.
+    /// Failed to write a file, e.g. the saved stack or an image.
     FailWritingFile,
     /// Failed to red the file.
     FailReadFile,
+    /// Failed to write to the output sink.
+    FailWritingOutput,
+    /// The saved stack contains something that is not a number.
+    InvalidStackFile,
+    /// The interpreter image is corrupted or uses an unsupported format version.
+    InvalidImage,
+    /// An address or a length points outside the data space.
+    InvalidAddress,
+    /// The data space has no room for more bytes.
+    DataSpaceOverflow,
+    /// A file includes itself, directly or through other files.
+    IncludeCycle,
+    /// `include` or `require` is not followed by the name of a file.
+    MissingFileName,
+    /// A `."`, `.(` or string literal is missing its closing delimiter.
+    UnterminatedLiteral,
     /// The output string is null or empty.
     StringNull,
     /// The character provided for the `emit` operation is invalid.
@@ -27,6 +44,99 @@ pub enum OperationError {
     InvalidIfFormat,
 }
 
+impl OperationError {
+    /// Checks if the error comes from `include`, `require` or `included` failing to load
+    /// a file.
+    pub fn is_include_error(&self) -> bool {
+        matches!(
+            self,
+            OperationError::FailReadFile
+                | OperationError::IncludeCycle
+                | OperationError::MissingFileName
+        )
+    }
+}
+
+/// Selects how runtime errors are written to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// Only the error itself, e.g. `stack-underflow`.
+    #[default]
+    Short,
+    /// The error with the failing token, the chain of user words being executed
+    /// and the source position, e.g.
+    /// `stack-underflow: '*' in 'square' called from 'main' at script.fth:12:5`.
+    Detailed,
+}
+
+/// Selects what the interpreter does with the remaining top-level tokens after one fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecoveryPolicy {
+    /// Stop at the first error.
+    Halt,
+    /// Skip the rest of the source line where the error happened.
+    SkipLine,
+    /// Keep executing the remaining tokens.
+    #[default]
+    Continue,
+}
+
+/// A runtime error together with the place where it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForthError {
+    /// The underlying error.
+    pub error: OperationError,
+    /// The token that failed, if the error comes from executing one.
+    pub token: Option<String>,
+    /// Name of the source (usually a file path) the top-level token comes from.
+    pub source: String,
+    /// Position of the top-level token that led to the error.
+    pub position: Option<SourcePosition>,
+    /// The user words that were executing, innermost first.
+    pub call_trace: Vec<String>,
+}
+
+impl ForthError {
+    /// Creates an error that is not tied to any token.
+    pub fn new(error: OperationError) -> Self {
+        ForthError {
+            error,
+            token: None,
+            source: String::new(),
+            position: None,
+            call_trace: Vec::new(),
+        }
+    }
+
+    /// Renders the error in the requested format, always ending with a newline.
+    pub fn format(&self, format: ErrorFormat) -> String {
+        match format {
+            ErrorFormat::Short => self.error.to_string(),
+            ErrorFormat::Detailed => self.to_string(),
+        }
+    }
+
+    /// Renders the short message preceded by the source and the line where the error
+    /// happened and, for an error including a file, by the path of that file, e.g.
+    /// `lib.fth:2: stack-underflow` or `main.fth:1: lib.fth: include-cycle`.
+    pub fn format_located(&self) -> String {
+        let mut message = String::new();
+        if let Some(position) = self.position {
+            message.push_str(&format!("{}:{}: ", self.source, position.line));
+        }
+        if let (true, Some(path)) = (self.error.is_include_error(), &self.token) {
+            message.push_str(&format!("{}: ", path));
+        }
+        message + &self.error.to_string()
+    }
+}
+
+impl From<OperationError> for ForthError {
+    fn from(error: OperationError) -> Self {
+        ForthError::new(error)
+    }
+}
+
 /// Represents possible errors that can occur during the validation of command-line arguments.
 ///
 /// These errors are used to handle incorrect or malformed input passed to the CLI.
@@ -38,14 +148,32 @@ pub enum CommandArgsError {
     /// Failed to parse the provided stack size value into a valid number.
     FailParseStackSize,
 
-    /// The provided argument format is incorrect (e.g., missing `=` in `--key=value`).
-    InvalidFormat,
+    /// An argument is neither a file, `-`, `-e <code>` nor an option.
+    InvalidFileFormat,
 
-    /// No input file was specified by the user.
-    FileNotSpecified,
+    /// The flag is not one of the supported flags.
+    UnknownOption(String),
 
-    /// The file argument is not in the expected position or format.
-    InvalidFileFormat,
+    /// The flag was passed more than once.
+    DuplicateOption(String),
+
+    /// The flag is not followed by its value.
+    MissingValue(String),
+
+    /// A value was given to a flag that does not take one.
+    UnexpectedValue(String),
+
+    /// The value of `--on-error` is not `halt`, `line` or `continue`.
+    InvalidRecoveryPolicy,
+
+    /// The value of `--format` is not `short` or `detailed`.
+    InvalidErrorFormat,
+
+    /// Two flags that cannot be used together were passed.
+    ConflictingOptions(String, String),
+
+    /// The `-e` flag is not followed by the code to run.
+    MissingInlineCode,
 }
 
 impl fmt::Display for CommandArgsError {
@@ -55,14 +183,37 @@ impl fmt::Display for CommandArgsError {
                 writeln!(f, "Stack size cannot be zero or less than or equal to one")
             }
             CommandArgsError::FailParseStackSize => {
-                writeln!(f, "There was an error parsing the number for stack-size")
+                writeln!(f, "There was an error parsing the number for --stack-size")
             }
             CommandArgsError::InvalidFileFormat => {
-                writeln!(f, "The first parameter should be the file to read")
+                writeln!(
+                    f,
+                    "Each parameter should be a file to read, '-', '-e <code>' or an option"
+                )
+            }
+            CommandArgsError::UnknownOption(flag) => {
+                writeln!(f, "Unknown option: {} (see --help)", flag)
+            }
+            CommandArgsError::DuplicateOption(flag) => {
+                writeln!(f, "The option {} was passed more than once", flag)
+            }
+            CommandArgsError::MissingValue(flag) => {
+                writeln!(f, "The option {} requires a value", flag)
+            }
+            CommandArgsError::UnexpectedValue(flag) => {
+                writeln!(f, "The option {} does not take a value", flag)
+            }
+            CommandArgsError::InvalidRecoveryPolicy => {
+                writeln!(f, "--on-error must be one of: halt, line, continue")
+            }
+            CommandArgsError::InvalidErrorFormat => {
+                writeln!(f, "--format must be one of: short, detailed")
             }
-            CommandArgsError::InvalidFormat => writeln!(f, "Invalid format: the '=' is missing "),
-            CommandArgsError::FileNotSpecified => {
-                writeln!(f, "The file to be read has not been specified ")
+            CommandArgsError::MissingInlineCode => {
+                writeln!(f, "-e must be followed by the code to run")
+            }
+            CommandArgsError::ConflictingOptions(first, second) => {
+                writeln!(f, "{} and {} cannot be used together", first, second)
             }
         }
     }
@@ -76,11 +227,28 @@ impl fmt::Display for OperationError {
             OperationError::DivisionByZero => writeln!(f, "division-by-zero"),
             OperationError::InvalidWord => writeln!(f, "invalid-word"),
             OperationError::FailWritingFile => {
-                writeln!(f, "We have a problem with writing the stack in stack.fht")
+                writeln!(f, "We have a problem with writing the file")
             }
             OperationError::FailReadFile => {
                 writeln!(f, "We have a problem with reading the file")
             }
+            OperationError::FailWritingOutput => {
+                writeln!(f, "We have a problem with writing the output")
+            }
+            OperationError::InvalidStackFile => {
+                writeln!(f, "The saved stack contains an invalid number")
+            }
+            OperationError::InvalidImage => {
+                writeln!(
+                    f,
+                    "The image is invalid or was saved with an unsupported version"
+                )
+            }
+            OperationError::InvalidAddress => writeln!(f, "invalid-memory-address"),
+            OperationError::DataSpaceOverflow => writeln!(f, "data-space-overflow"),
+            OperationError::IncludeCycle => writeln!(f, "include-cycle"),
+            OperationError::MissingFileName => writeln!(f, "missing-file-name"),
+            OperationError::UnterminatedLiteral => writeln!(f, "unterminated-literal"),
             OperationError::StringNull => {
                 writeln!(f, "The output string is Null")
             }
@@ -99,3 +267,26 @@ impl fmt::Display for OperationError {
         }
     }
 }
+
+impl fmt::Display for ForthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error.to_string().trim_end())?;
+        if let Some(token) = &self.token {
+            write!(f, ": '{}'", token)?;
+        }
+        if let Some((innermost, callers)) = self.call_trace.split_first() {
+            write!(f, " in '{}'", innermost)?;
+            for caller in callers {
+                write!(f, " called from '{}'", caller)?;
+            }
+        }
+        if let Some(position) = self.position {
+            write!(
+                f,
+                " at {}:{}:{}",
+                self.source, position.line, position.column
+            )?;
+        }
+        writeln!(f)
+    }
+}
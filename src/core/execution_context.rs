@@ -0,0 +1,79 @@
+use crate::core::data_space::DataSpace;
+use crate::core::error::OperationError;
+use crate::core::operation::word_definition::WordRegistry;
+use crate::core::output_sink::OutputSink;
+use crate::core::run_result::Halt;
+
+/// The interpreter state shared with every operation.
+///
+/// `ForthCalculator` owns a single context and hands it to each operation it executes,
+/// so operations can read and modify the stack, write output, inspect the token that
+/// invoked them or look up user-defined words through the same value.
+#[derive(Debug)]
+pub struct ExecutionContext {
+    /// Stack that stores numeric values for operations.
+    pub stack: Vec<i16>,
+    /// Maximum allowed stack size.
+    pub max_stack_size: i16,
+    /// Destination of the text produced by output operations.
+    pub output: OutputSink,
+    /// The token being executed, as it appears in the source (e.g. `." hello"`).
+    pub token: String,
+    /// Words defined by the user.
+    pub word_registry: WordRegistry,
+    /// Memory addressable by Forth code.
+    pub data_space: DataSpace,
+    /// Address and length in the data space of each argument passed to the program,
+    /// starting with the name of the script.
+    pub args: Vec<(i16, i16)>,
+    /// Index of the argument `next-arg` returns next.
+    pub next_arg: usize,
+    /// Set by `bye` or `exit-code`. Once set, the interpreter stops executing the
+    /// remaining tokens.
+    pub halted: Option<Halt>,
+}
+
+impl ExecutionContext {
+    /// Creates an empty context whose stack can hold up to `max_stack_size` elements.
+    pub fn new(max_stack_size: i16) -> Self {
+        ExecutionContext {
+            stack: Vec::new(),
+            max_stack_size,
+            output: OutputSink::default(),
+            token: String::new(),
+            word_registry: WordRegistry::new(),
+            data_space: DataSpace::new(),
+            args: Vec::new(),
+            next_arg: 1,
+            halted: None,
+        }
+    }
+
+    /// Creates a context that starts with the given stack and no size limit.
+    pub fn with_stack(stack: Vec<i16>) -> Self {
+        ExecutionContext {
+            stack,
+            ..ExecutionContext::new(i16::MAX)
+        }
+    }
+
+    /// Pushes a value onto the stack.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::StackOverflow)` if the stack is already full.
+    pub fn push(&mut self, value: i16) -> Result<(), OperationError> {
+        if self.stack.len() >= self.max_stack_size as usize {
+            return Err(OperationError::StackOverflow);
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Pops the top value of the stack.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::StackUnderflow)` if the stack is empty.
+    pub fn pop(&mut self) -> Result<i16, OperationError> {
+        self.stack.pop().ok_or(OperationError::StackUnderflow)
+    }
+}
@@ -1,31 +1,91 @@
 use super::operation::conditional_module::Conditional;
-use super::operation::OperationOutput;
 use super::operation::OperationType;
-use crate::core::error::OperationError;
-use crate::core::operation::word_definition::WordRegistry;
+use crate::core::data_space::DataSpace;
+use crate::core::error::{ErrorFormat, ForthError, OperationError, RecoveryPolicy};
+use crate::core::execution_context::ExecutionContext;
+use crate::core::image::{Image, ImageWord};
+use crate::core::operation::get_all_standar_operations;
+use crate::core::operation::word_definition::Definition;
 use crate::core::operation::Operation;
-use crate::core::operation::{get_all_standar_operations, get_output_operations};
-use crate::utils::file_manager;
+use crate::core::output_sink::OutputSink;
+use crate::core::run_result::{ExecutionStats, RunResult};
+use crate::core::stack_persistence::StackPersistence;
+use crate::utils::file_manager::{self, SourcePosition, SourceToken};
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
 
 const CANONIC_SUBFIX: &str = "c";
 
+/// Source name used in error messages when none has been set.
+const DEFAULT_SOURCE_NAME: &str = "<input>";
+
+/// Loads the file whose name follows it.
+const INCLUDE_DIRECTIVE: &str = "include";
+
+/// Loads the file whose name follows it, unless it was already loaded.
+const REQUIRE_DIRECTIVE: &str = "require";
+
+/// Loads the file whose name is the string on top of the stack.
+const INCLUDED_DIRECTIVE: &str = "included";
+
+/// Runs the string on top of the stack as Forth code.
+const EVALUATE_WORD: &str = "evaluate";
+
+/// Appended to the source name while a string runs with `evaluate`. The name keeps the
+/// path of the source, so the files the string includes are looked up next to it.
+const EVALUATE_SOURCE_SUFFIX: &str = " (evaluate)";
+
 /// A stack-based calculator implementing a subset of the Forth language.
 /// This calculator supports arithmetic operations, boolean operations,
 /// stack manipulation, and output operations.
 pub struct ForthCalculator {
-    /// Maximum allowed stack size.
-    max_stack_size: i16,
-    /// Stack that stores numeric values for operations.
-    stack: Vec<i16>,
-    /// Mapping of standard operations (e.g., arithmetic, boolean) to their implementations.
+    /// State shared with the operations: stack, output and user-defined words.
+    context: ExecutionContext,
+    /// Mapping of every builtin operation (arithmetic, boolean, stack, output) to its implementation.
     operations: HashMap<OperationType, Box<dyn Operation>>,
-    /// Mapping of output-related operations (e.g., printing, emitting characters).
-    output_operations: HashMap<OperationType, Box<dyn OperationOutput>>,
-
-    word_registry: WordRegistry,
 
-    output: String,
+    /// How errors are rendered in the output.
+    error_format: ErrorFormat,
+    /// Destination of the error messages; `None` interleaves them with the output.
+    error_output: Option<OutputSink>,
+    /// Errors reported by the last `run`.
+    errors: Vec<ForthError>,
+    /// Destination of the execution trace, if tracing is enabled.
+    trace_output: Option<OutputSink>,
+    /// Statistics of the evaluation in progress.
+    stats: ExecutionStats,
+    /// Where the stack is saved after every evaluation.
+    stack_persistence: StackPersistence,
+    /// What happens with the remaining tokens after one fails.
+    recovery_policy: RecoveryPolicy,
+    /// Whether a `run` that reports an error restores the stack and dictionary.
+    transactional: bool,
+    /// Name of the source being run, used in detailed error messages.
+    source_name: String,
+    /// Names of the user words currently executing, outermost first.
+    call_trace: Vec<String>,
+    /// The innermost failing token and the call trace at that point, recorded
+    /// while an error unwinds through nested words.
+    failure: Option<(String, Vec<String>)>,
+    /// Whether the error unwinding now was already reported by the code run with
+    /// `evaluate`.
+    failure_reported: bool,
+    /// Source received by `feed` that does not form a complete construct yet.
+    pending_input: String,
+    /// Position of the first character of `pending_input` in the fed source.
+    pending_position: SourcePosition,
+    /// Canonical paths of the sources being run through an include, outermost first,
+    /// starting with the source that includes them when it is a file. It is empty while
+    /// no included file runs.
+    include_stack: Vec<PathBuf>,
+    /// Canonical paths of every file included so far, used by `require`.
+    included_files: Vec<PathBuf>,
+    /// Directories where included files are looked up when they are not found next to
+    /// the file that includes them.
+    search_path: Vec<PathBuf>,
 }
 
 impl ForthCalculator {
@@ -36,22 +96,310 @@ impl ForthCalculator {
     /// * `stack_size` - The maximum number of elements allowed in the stack.
     pub fn new(stack_size: i16) -> Self {
         ForthCalculator {
-            max_stack_size: stack_size,
-            stack: Vec::new(),
+            context: ExecutionContext::new(stack_size),
             operations: get_all_standar_operations(),
-            output_operations: get_output_operations(),
-            output: String::new(),
-            word_registry: WordRegistry::new(),
+            error_format: ErrorFormat::default(),
+            error_output: None,
+            errors: Vec::new(),
+            trace_output: None,
+            stats: ExecutionStats::default(),
+            stack_persistence: StackPersistence::default(),
+            recovery_policy: RecoveryPolicy::default(),
+            transactional: false,
+            source_name: DEFAULT_SOURCE_NAME.to_string(),
+            call_trace: Vec::new(),
+            failure: None,
+            failure_reported: false,
+            pending_input: String::new(),
+            pending_position: SourcePosition { line: 1, column: 1 },
+            include_stack: Vec::new(),
+            included_files: Vec::new(),
+            search_path: Vec::new(),
+        }
+    }
+
+    /// Creates a `ForthCalculator` whose stack starts with the given values.
+    ///
+    /// # Arguments
+    ///
+    /// * `stack_size` - The maximum number of elements allowed in the stack.
+    /// * `stack` - The initial values, from bottom to top.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::StackOverflow)` if there are more values than `stack_size`.
+    pub fn with_stack(stack_size: i16, stack: Vec<i16>) -> Result<Self, OperationError> {
+        if stack.len() > stack_size.max(0) as usize {
+            return Err(OperationError::StackOverflow);
+        }
+        let mut calculator = ForthCalculator::new(stack_size);
+        calculator.context.stack = stack;
+        Ok(calculator)
+    }
+
+    /// Creates a `ForthCalculator` whose stack starts with the values saved in a file,
+    /// so the state left by a previous run can be picked up.
+    ///
+    /// # Arguments
+    ///
+    /// * `stack_size` - The maximum number of elements allowed in the stack.
+    /// * `path` - A file written by the `StackPersistence::File` policy.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::FailReadFile)` if the file cannot be read.
+    /// * `Err(OperationError::InvalidStackFile)` if it contains something other than numbers.
+    /// * `Err(OperationError::StackOverflow)` if there are more values than `stack_size`.
+    pub fn from_stack_file<P: AsRef<Path>>(
+        stack_size: i16,
+        path: P,
+    ) -> Result<Self, OperationError> {
+        ForthCalculator::with_stack(stack_size, file_manager::load_stack(path)?)
+    }
+
+    /// Creates a `ForthCalculator` from a snapshot taken with `image`.
+    ///
+    /// Native words are restored without their implementation: they fail with
+    /// `WordNotFound` until they are registered again with `register_native`.
+    pub fn from_image(image: Image) -> Self {
+        let mut calculator = ForthCalculator::new(image.max_stack_size);
+        calculator.context.stack = image.stack;
+        calculator.context.data_space = DataSpace::from_bytes(image.data_space);
+        for word in image.words {
+            match word.body {
+                Some(body) => calculator
+                    .context
+                    .word_registry
+                    .define_word(word.name, body),
+                None => calculator
+                    .context
+                    .word_registry
+                    .define_unbound_native(word.name),
+            }
         }
+        calculator.error_format = image.error_format;
+        calculator.recovery_policy = image.recovery_policy;
+        calculator.transactional = image.transactional;
+        calculator.source_name = image.source_name;
+        calculator.included_files = image.included_files;
+        calculator
     }
 
-    pub fn get_output(&self) -> &String {
-        &self.output
+    /// Restores a `ForthCalculator` from an image file written by `save_image`.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::FailReadFile)` if the file cannot be read.
+    /// * `Err(OperationError::InvalidImage)` if the file is not a valid image.
+    pub fn load_image<P: AsRef<Path>>(path: P) -> Result<Self, OperationError> {
+        let bytes = std::fs::read(path).map_err(|_| OperationError::FailReadFile)?;
+        Ok(ForthCalculator::from_image(Image::decode(&bytes)?))
+    }
+
+    /// Takes a snapshot of the stack, the data space, every word definition, the files
+    /// included so far and the settings.
+    ///
+    /// The output sinks and the stack persistence policy are not part of the snapshot.
+    pub fn image(&self) -> Image {
+        Image {
+            max_stack_size: self.context.max_stack_size,
+            stack: self.context.stack.clone(),
+            data_space: self.context.data_space.as_bytes().to_vec(),
+            words: self
+                .context
+                .word_registry
+                .words
+                .iter()
+                .map(|word| ImageWord {
+                    name: word.name.clone(),
+                    body: word.body().map(<[String]>::to_vec),
+                })
+                .collect(),
+            error_format: self.error_format,
+            recovery_policy: self.recovery_policy,
+            transactional: self.transactional,
+            source_name: self.source_name.clone(),
+            included_files: self.included_files.clone(),
+        }
+    }
+
+    /// Writes a snapshot of the interpreter to a file, so it can be restored with
+    /// `load_image` without processing the source again.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::FailWritingFile)` if the file cannot be written.
+    pub fn save_image<P: AsRef<Path>>(&self, path: P) -> Result<(), OperationError> {
+        std::fs::write(path, self.image().encode()).map_err(|_| OperationError::FailWritingFile)
+    }
+
+    /// Returns the output kept in memory by the last `run`.
+    ///
+    /// It is empty when the output is streamed to a writer or a callback.
+    pub fn get_output(&self) -> &str {
+        self.context.output.as_str()
+    }
+
+    /// Sets where the text produced by `.`, `emit`, `cr` and `."` (and the error
+    /// messages) goes.
+    ///
+    /// By default it is kept in memory and can be read with `get_output`. A writer or a
+    /// callback receives the text as soon as each operation executes.
+    pub fn set_output(&mut self, output: OutputSink) {
+        self.context.output = output;
+    }
+
+    /// Sends error messages to their own sink instead of interleaving them with the
+    /// program output.
+    ///
+    /// Until this is called, errors are written to the output as they happen, which
+    /// is the historical behavior.
+    pub fn set_error_output(&mut self, error_output: OutputSink) {
+        self.error_output = Some(error_output);
+    }
+
+    /// Returns the error messages kept in memory by the last `run`.
+    ///
+    /// It is empty when errors are interleaved with the output or streamed to a writer
+    /// or a callback.
+    pub fn get_error_output(&self) -> &str {
+        self.error_output.as_ref().map_or("", OutputSink::as_str)
+    }
+
+    /// Enables the execution trace: every token is written to `trace_output`, indented
+    /// by the depth of the user words being executed, together with the stack it finds.
+    ///
+    /// # Example
+    /// ```text
+    /// calculator.set_trace_output(OutputSink::writer(io::stderr()));
+    /// calculator.run(": square dup * ; 3 square".to_string());
+    /// // 3 (  )
+    /// // square ( 3 )
+    /// //   dup ( 3 )
+    /// //   * ( 3 3 )
+    /// ```
+    pub fn set_trace_output(&mut self, trace_output: OutputSink) {
+        self.trace_output = Some(trace_output);
+    }
+
+    /// Returns the execution trace kept in memory by the last `run`.
+    ///
+    /// It is empty when tracing is disabled or the trace is streamed to a writer or a
+    /// callback.
+    pub fn get_trace_output(&self) -> &str {
+        self.trace_output.as_ref().map_or("", OutputSink::as_str)
+    }
+
+    /// Returns the errors reported by the last `run`, in the order they happened.
+    pub fn get_errors(&self) -> &[ForthError] {
+        &self.errors
     }
 
     /// Returns a reference to the current stack.
     pub fn get_stack(&self) -> &Vec<i16> {
-        &self.stack
+        &self.context.stack
+    }
+
+    /// Registers a word implemented in Rust.
+    ///
+    /// The name is case-insensitive and follows the same rules as a colon definition:
+    /// it shadows any builtin or user word with the same name for the code that runs
+    /// afterwards, while words defined before keep calling the previous definition.
+    /// Any closure taking the `ExecutionContext` can be registered.
+    ///
+    /// If the calculator was restored from an image that contains native words with
+    /// this name, they are bound to `operation` instead of defining a new version.
+    ///
+    /// # Example
+    /// ```text
+    /// calculator.register_native("double", |context: &mut ExecutionContext| {
+    ///     let value = context.pop()?;
+    ///     context.push(value * 2)
+    /// });
+    /// calculator.run("21 double".to_string()); // stack: [42]
+    /// ```
+    pub fn register_native<O: Operation + 'static>(&mut self, name: &str, operation: O) {
+        let name = name.to_lowercase();
+        let operation: Rc<dyn Operation> = Rc::new(operation);
+        if !self
+            .context
+            .word_registry
+            .bind_native(&name, Rc::clone(&operation))
+        {
+            self.context.word_registry.define_native(name, operation);
+        }
+    }
+
+    /// Sets how errors are written to the output.
+    ///
+    /// `ErrorFormat::Short` (the default) keeps the historical messages such as
+    /// `stack-underflow`, while `ErrorFormat::Detailed` adds the failing token,
+    /// the chain of user words and the source position.
+    pub fn set_error_format(&mut self, error_format: ErrorFormat) {
+        self.error_format = error_format;
+    }
+
+    /// Sets what happens with the remaining top-level tokens after one fails.
+    ///
+    /// `RecoveryPolicy::Continue` (the default) executes every token, `RecoveryPolicy::SkipLine`
+    /// resumes on the next source line and `RecoveryPolicy::Halt` stops at the first error.
+    pub fn set_recovery_policy(&mut self, recovery_policy: RecoveryPolicy) {
+        self.recovery_policy = recovery_policy;
+    }
+
+    /// Returns what happens with the remaining top-level tokens after one fails.
+    pub fn get_recovery_policy(&self) -> RecoveryPolicy {
+        self.recovery_policy
+    }
+
+    /// Makes every call to `run` atomic.
+    ///
+    /// When enabled, a `run` that reports an error leaves the stack, the word
+    /// definitions and the data space exactly as they were before it started. The error
+    /// is still written to the output.
+    pub fn set_transactional(&mut self, transactional: bool) {
+        self.transactional = transactional;
+    }
+
+    /// Sets where the stack is saved after every evaluation.
+    ///
+    /// Nothing is saved by default. A failure while saving is reported as a
+    /// `FailWritingFile` error.
+    pub fn set_stack_persistence(&mut self, stack_persistence: StackPersistence) {
+        self.stack_persistence = stack_persistence;
+    }
+
+    /// Sets the directories where `include`, `require` and `included` look for files that
+    /// are not found next to the file that includes them, in order.
+    pub fn set_search_path<P: AsRef<Path>>(&mut self, search_path: &[P]) {
+        self.search_path = search_path
+            .iter()
+            .map(|dir| dir.as_ref().to_path_buf())
+            .collect();
+    }
+
+    /// Sets the name of the source being run (usually its file path), used in
+    /// detailed error messages.
+    pub fn set_source_name(&mut self, source_name: &str) {
+        self.source_name = source_name.to_string();
+    }
+
+    /// Sets the arguments that `argc` and `arg` give to the running code.
+    ///
+    /// The arguments are stored in the data space, so `arg` can push their address and
+    /// length. By convention the first argument is the name of the script, so `next-arg`
+    /// starts from the second one.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::DataSpaceOverflow)` if the data space has no room for them.
+    pub fn set_args<S: AsRef<str>>(&mut self, args: &[S]) -> Result<(), OperationError> {
+        self.context.args = args
+            .iter()
+            .map(|arg| self.context.data_space.store_string(arg.as_ref()))
+            .collect::<Result<Vec<(i16, i16)>, OperationError>>()?;
+        self.context.next_arg = 1;
+        Ok(())
+    }
+
+    /// Returns the data space, e.g. to read a string whose address is on the stack.
+    pub fn get_data_space(&self) -> &DataSpace {
+        &self.context.data_space
     }
 
     /// Checks if a word definition in the token list is valid.
@@ -70,45 +418,6 @@ impl ForthCalculator {
         total_colons == total_semicolons && all_colons_valid && all_semicolons_valid
     }
 
-    /// Validates that the provided tokens are syntactically and semantically correct.
-    ///
-    /// This function performs two checks:
-    /// 1. If there is a custom word definition (indicated by a colon `:`),
-    ///    it must be valid as defined by `is_valid_word_definition`.
-    /// 2. All tokens must either be:
-    ///    - A valid integer
-    ///    - A known predefined operation
-    ///    - A user-defined word
-    ///     
-    fn are_valid_tokens(&mut self, tokens: &mut Vec<String>) -> Result<(), OperationError> {
-        self.extract_words(tokens)?;
-        for token in tokens {
-            if let Err(_error) = token.parse::<i16>() {
-                let parts: Vec<&str> = token.split('_').collect();
-                let word_exists = if parts.len() == 2 {
-                    let name = parts[0];
-                    if parts[1] != CANONIC_SUBFIX {
-                        if let Ok(index) = parts[1].parse::<usize>() {
-                            self.word_registry.contains_key(name)
-                                && self.word_registry.has_version(name, index)
-                        } else {
-                            false
-                        }
-                    } else {
-                        true
-                    }
-                } else {
-                    self.word_registry.contains_key(token)
-                };
-                if !word_exists {
-                    return Err(OperationError::WordNotFound);
-                }
-            }
-        }
-
-        Ok(())
-    }
-
     /// Extracts and registers custom word definitions (also known as `words`) from the provided token list
     /// into the `WordRegistry`. It also removes the word definitions from the original token list
     /// to ensure only executable tokens remain.
@@ -141,53 +450,121 @@ impl ForthCalculator {
     /// ```
     ///
     pub fn extract_words(&mut self, tokens: &mut Vec<String>) -> Result<(), OperationError> {
-        let total_colons = tokens.iter().filter(|token| token == &":").count();
-        if total_colons != 0 && !self.is_valid_word_definition(tokens) {
-            return Err(OperationError::InvalidWordFormat);
+        let mut source_tokens: Vec<SourceToken> = tokens
+            .iter()
+            .map(|text| SourceToken {
+                text: text.to_string(),
+                position: SourcePosition { line: 1, column: 1 },
+                span: 0..0,
+            })
+            .collect();
+        self.extract_source_words(&mut source_tokens)
+            .map_err(|error| error.error)?;
+
+        tokens.drain(..);
+        tokens.extend(source_tokens.into_iter().map(|token| token.text));
+        Ok(())
+    }
+
+    /// Same as [`ForthCalculator::extract_words`], keeping the source position of
+    /// every remaining token so that errors can be located.
+    fn extract_source_words(&mut self, tokens: &mut Vec<SourceToken>) -> Result<(), ForthError> {
+        let unterminated = tokens
+            .iter()
+            .find(|token| file_manager::is_unterminated_literal(&token.text));
+        if let Some(literal) = unterminated {
+            return Err(self.locate_error(OperationError::UnterminatedLiteral, literal));
+        }
+        let texts: Vec<String> = tokens.iter().map(|token| token.text.clone()).collect();
+        let first_colon = tokens.iter().find(|token| token.text == ":");
+        if let Some(colon) = first_colon {
+            if !self.is_valid_word_definition(&texts) {
+                return Err(self.locate_error(OperationError::InvalidWordFormat, colon));
+            }
         }
 
-        let mut transformed_tokens: Vec<String> = Vec::new();
-        let mut tokens_iter = tokens.iter_mut().peekable();
+        let mut transformed_tokens: Vec<SourceToken> = Vec::new();
+        let mut tokens_iter = tokens.iter().peekable();
 
-        while let Some(token) = tokens_iter.next() {
-            let mut token = token.to_lowercase();
+        while let Some(source_token) = tokens_iter.next() {
+            let mut token = normalize_token(&source_token.text);
             if token == ":" {
                 if let Some(word_name) = tokens_iter.next() {
-                    if word_name.parse::<i16>().is_ok() {
-                        return Err(OperationError::InvalidWord);
+                    if word_name.text.parse::<i16>().is_ok() {
+                        return Err(self.locate_error(OperationError::InvalidWord, word_name));
                     }
                     let mut body = vec![];
 
-                    for def_token in tokens_iter.by_ref() {
-                        let mut def_token = def_token.to_lowercase();
+                    for def_source_token in tokens_iter.by_ref() {
+                        let mut def_token = normalize_token(&def_source_token.text);
                         if def_token == ";" {
                             break;
+                        } else if OperationType::from_token(&def_token)
+                            == Some(OperationType::ImmediateText)
+                        {
+                            // `.(` is not compiled: it prints when the definition is reached.
+                            transformed_tokens.push(SourceToken {
+                                text: format!("{}_{}", def_token, CANONIC_SUBFIX),
+                                position: def_source_token.position,
+                                span: def_source_token.span.clone(),
+                            });
+                        } else if let Some(text) = file_manager::parse_string_literal(&def_token) {
+                            let (address, len) =
+                                self.store_string_literal(&text, def_source_token)?;
+                            body.push(address.to_string());
+                            body.push(len.to_string());
                         } else {
                             self.append_word_version_suffix(&mut def_token);
                             body.push(def_token.to_string());
                         }
                     }
 
-                    self.word_registry
-                        .define_word(word_name.to_lowercase().to_string(), body);
+                    self.context
+                        .word_registry
+                        .define_word(word_name.text.to_lowercase().to_string(), body);
+                }
+            } else if let Some(text) = file_manager::parse_string_literal(&token) {
+                let (address, len) = self.store_string_literal(&text, source_token)?;
+                for value in [address, len] {
+                    transformed_tokens.push(SourceToken {
+                        text: value.to_string(),
+                        position: source_token.position,
+                        span: source_token.span.clone(),
+                    });
                 }
             } else {
                 self.append_word_version_suffix(&mut token);
-                transformed_tokens.push(token);
+                transformed_tokens.push(SourceToken {
+                    text: token,
+                    position: source_token.position,
+                    span: source_token.span.clone(),
+                });
             }
         }
-        tokens.drain(..);
-        tokens.extend(transformed_tokens);
+        *tokens = transformed_tokens;
         Ok(())
     }
 
+    /// Stores the text of a string literal in the data space, so the literal can be
+    /// replaced by its address and length.
+    fn store_string_literal(
+        &mut self,
+        text: &str,
+        token: &SourceToken,
+    ) -> Result<(i16, i16), ForthError> {
+        self.context
+            .data_space
+            .store_string(text)
+            .map_err(|error| self.locate_error(error, token))
+    }
+
     fn append_word_version_suffix(&self, token: &mut String) {
-        if let Some(word_versions) = self.word_registry.get_word_versions(token) {
+        if let Some(word_versions) = self.context.word_registry.get_word_versions(token) {
             if let Some(last_index) = word_versions.last() {
                 token.push_str(&format!("_{}", last_index));
             }
         } else if OperationType::from_token(token).is_some() {
-            if let Some(word_versions) = self.word_registry.get_word_versions(token) {
+            if let Some(word_versions) = self.context.word_registry.get_word_versions(token) {
                 if let Some(last_index) = word_versions.last() {
                     token.push_str(&format!("_{}", last_index));
                 }
@@ -203,32 +580,382 @@ impl ForthCalculator {
     ///
     /// * `content` - The input string containing operations and numbers.
     ///
-    /// The function iterates over the tokens, executing either number insertion or operations.
-    /// If an error occurs, it is logged and execution stops.
+    /// This is a thin wrapper around [`ForthCalculator::evaluate`] for callers that read
+    /// the output and the stack afterwards.
     pub fn run(&mut self, content: String) {
-        let mut output: String = String::new();
-        let mut input_tokens = file_manager::tokenize(&content);
+        self.evaluate(&content);
+    }
 
-        if let Err(error) = self.are_valid_tokens(&mut input_tokens) {
-            self.add_string_output_error(&mut output, error);
-        } else {
-            self.process_tokens(&input_tokens, &mut output);
+    /// Evaluates the given code and returns everything it produced.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The input string containing operations and numbers.
+    ///
+    /// The function iterates over the tokens, executing either number insertion or operations.
+    /// Errors are reported and execution continues according to the recovery policy.
+    /// In transactional mode, an error also restores the stack and the word definitions.
+    ///
+    /// # Example
+    /// ```text
+    /// let result = calculator.evaluate(": square dup * ; 3 square");
+    /// assert_eq!(result.stack, vec![9]);
+    /// assert_eq!(result.defined_words, vec!["square"]);
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn evaluate(&mut self, content: &str) -> RunResult {
+        self.evaluate_tokens(file_manager::tokenize_with_positions(content))
+    }
+
+    /// Evaluates a chunk of a source that arrives in pieces, e.g. line by line.
+    ///
+    /// Every complete construct is executed right away, while an unfinished one (a
+    /// `: ... ;` definition, an `if ... then`, a `."` literal without its closing quote
+    /// or a word that may continue in the next chunk) stays pending until a later
+    /// chunk completes it. Positions in error messages are counted from the start of
+    /// the first chunk.
+    ///
+    /// # Example
+    /// ```text
+    /// calculator.feed(": square dup\n");  // nothing runs yet
+    /// let result = calculator.feed("* ; 3 square\n");
+    /// assert_eq!(result.stack, vec![9]);
+    /// ```
+    pub fn feed(&mut self, chunk: &str) -> RunResult {
+        self.pending_input.push_str(chunk);
+        let tokens =
+            file_manager::tokenize_from_position(&self.pending_input, self.pending_position);
+        let input_len = self.pending_input.chars().count();
+        let complete = complete_tokens_len(&tokens, input_len);
+
+        let pending_input = std::mem::take(&mut self.pending_input);
+        match tokens.get(complete) {
+            Some(first_pending) => {
+                self.pending_position = first_pending.position;
+                self.pending_input = pending_input
+                    .chars()
+                    .skip(first_pending.span.start)
+                    .collect();
+            }
+            None => {
+                self.pending_position =
+                    file_manager::position_after(&pending_input, self.pending_position);
+            }
+        }
+        self.evaluate_tokens(tokens.into_iter().take(complete).collect())
+    }
+
+    /// Evaluates whatever `feed` left pending, as if the source ended there, and starts
+    /// counting positions from the beginning again.
+    ///
+    /// Unfinished constructs are reported the same way `evaluate` reports them.
+    pub fn finish(&mut self) -> RunResult {
+        let pending_input = std::mem::take(&mut self.pending_input);
+        let tokens = file_manager::tokenize_from_position(&pending_input, self.pending_position);
+        self.pending_position = SourcePosition { line: 1, column: 1 };
+        self.evaluate_tokens(tokens)
+    }
+
+    /// Runs a file as if it were included with `include`, e.g. to load a prelude before
+    /// the user code.
+    ///
+    /// The path is resolved from the current directory and then from the search path,
+    /// and the file counts as included for later `require`s.
+    ///
+    /// # Example
+    /// ```text
+    /// calculator.set_search_path(&["/usr/share/forth"]);
+    /// let result = calculator.include("prelude.fth");
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn include(&mut self, path: &str) -> RunResult {
+        self.evaluate_with(
+            |calculator| match calculator.include_file(path, false, None) {
+                Ok(failed) => failed,
+                Err(error) => {
+                    let error = ForthError {
+                        token: Some(path.to_string()),
+                        ..ForthError::new(error)
+                    };
+                    calculator.add_string_output_error(error);
+                    true
+                }
+            },
+        )
+    }
+
+    /// Checks if `feed` is holding an unfinished construct.
+    pub fn has_pending_input(&self) -> bool {
+        !self.pending_input.trim().is_empty()
+    }
+
+    /// Runs already tokenized code and returns everything it produced.
+    fn evaluate_tokens(&mut self, input_tokens: Vec<SourceToken>) -> RunResult {
+        self.evaluate_with(|calculator| calculator.run_tokens(input_tokens))
+    }
+
+    /// Runs `run`, which returns whether anything failed, as a single evaluation: the
+    /// output and the errors start empty, a failure is rolled back in transactional mode
+    /// and the stack is saved afterwards.
+    fn evaluate_with<F: FnOnce(&mut Self) -> bool>(&mut self, run: F) -> RunResult {
+        let start = Instant::now();
+        self.context.output.clear();
+        self.errors.clear();
+        self.stats = ExecutionStats::default();
+        self.context.halted = None;
+        if let Some(error_output) = &mut self.error_output {
+            error_output.clear();
+        }
+        if let Some(trace_output) = &mut self.trace_output {
+            trace_output.clear();
+        }
+        let snapshot = self
+            .transactional
+            .then(|| (self.context.stack.clone(), self.context.data_space.clone()));
+        let registry_snapshot = self.context.word_registry.words.len();
+        let included_files_snapshot = self.included_files.len();
+
+        let failed = run(self);
+
+        if let (true, Some((stack, data_space))) = (failed, snapshot) {
+            self.context.stack = stack;
+            self.context.data_space = data_space;
+            self.context.word_registry.truncate(registry_snapshot);
+            self.included_files.truncate(included_files_snapshot);
+        }
+
+        if let Err(_e) = self.stack_persistence.save(&self.context.stack) {
+            self.add_string_output_error(OperationError::FailWritingFile.into());
+        }
+
+        self.stats.elapsed = start.elapsed();
+        RunResult {
+            stack: self.context.stack.clone(),
+            output: self.get_output().to_string(),
+            errors: self.errors.clone(),
+            defined_words: self.context.word_registry.words[registry_snapshot..]
+                .iter()
+                .map(|word| word.name.clone())
+                .collect(),
+            stats: self.stats.clone(),
+            halted: self.context.halted,
+        }
+    }
+
+    /// Compiles and runs tokenized code, loading the files it includes as they are reached.
+    ///
+    /// The code is split at every top-level `include`, `require` and `included`, and right
+    /// after every top-level `evaluate`. The definitions of each part are only extracted
+    /// once the previous ones ran, so it can use the words defined by the files and strings
+    /// run before it. Unknown words fail when they are reached, like any other error.
+    ///
+    /// # Returns
+    /// `true` if any token failed, here or in an included file.
+    fn run_tokens(&mut self, mut tokens: Vec<SourceToken>) -> bool {
+        let mut failed = false;
+        let mut skipped_line = None;
+        loop {
+            let mut rest = tokens.split_off(segment_end(&tokens));
+
+            if let Err(error) = self.extract_source_words(&mut tokens) {
+                self.add_string_output_error(error);
+                return true;
+            }
+            failed |= self.process_tokens(&tokens, &mut skipped_line);
+            let halted = failed && self.recovery_policy == RecoveryPolicy::Halt;
+            if rest.is_empty() || halted || self.context.halted.is_some() {
+                return failed;
+            }
+            if !is_include_directive(&rest[0]) {
+                tokens = rest;
+                continue;
+            }
+
+            let directive = rest.remove(0);
+            let directive_name = directive.text.to_lowercase();
+            let file_token = match directive_name.as_str() {
+                INCLUDED_DIRECTIVE => None,
+                _ if rest.is_empty() => {
+                    let error = self.locate_error(OperationError::MissingFileName, &directive);
+                    self.add_string_output_error(error);
+                    return true;
+                }
+                _ => Some(rest.remove(0)),
+            };
+            tokens = rest;
+            if skipped_line == Some(directive.position.line) {
+                continue;
+            }
+
+            let location = file_token.as_ref().unwrap_or(&directive);
+            let included = match &file_token {
+                Some(file_token) => Ok(file_token.text.clone()),
+                None => self.pop_string(),
+            };
+            let source_name = self.source_name.clone();
+            let included = match included {
+                Ok(path) => self
+                    .include_file(
+                        &path,
+                        directive_name == REQUIRE_DIRECTIVE,
+                        Some(&source_name),
+                    )
+                    .map_err(|error| (error, path)),
+                Err(error) => Err((error, directive.text.clone())),
+            };
+            let included_failed = match included {
+                Ok(included_failed) => included_failed,
+                Err((error, token)) => {
+                    let error = ForthError {
+                        error,
+                        token: Some(token),
+                        source: self.source_name.clone(),
+                        position: Some(location.position),
+                        call_trace: Vec::new(),
+                    };
+                    self.add_string_output_error(error);
+                    true
+                }
+            };
+            if included_failed {
+                failed = true;
+                match self.recovery_policy {
+                    RecoveryPolicy::Halt => return true,
+                    RecoveryPolicy::SkipLine => skipped_line = Some(location.position.line),
+                    RecoveryPolicy::Continue => {}
+                }
+            }
+            if self.context.halted.is_some() {
+                return failed;
+            }
         }
+    }
 
-        if let Err(_e) = file_manager::save_stack(&self.stack) {
-            self.add_string_output_error(&mut output, OperationError::FailWritingFile);
+    /// Runs the string on top of the stack as Forth code, in the current context.
+    ///
+    /// The string is run like the code passed to `run`: it can include files, its errors
+    /// are reported with their position in the string and the recovery policy decides
+    /// what runs after them. If any part of it failed, `evaluate` fails with the first
+    /// error, which is not reported again.
+    fn evaluate_string(&mut self) -> Result<(), OperationError> {
+        let code = self.pop_string()?;
+        let first_error = self.errors.len();
+        let evaluate_source_name = format!("{}{}", self.source_name, EVALUATE_SOURCE_SUFFIX);
+        let source_name = std::mem::replace(&mut self.source_name, evaluate_source_name);
+        let failed = self.run_tokens(file_manager::tokenize_with_positions(&code));
+        self.source_name = source_name;
+        match self.errors.get(first_error) {
+            Some(error) if failed => {
+                self.failure_reported = true;
+                Err(error.error.clone())
+            }
+            _ => Ok(()),
         }
-        self.output = output;
     }
 
-    /// Appends an error message to the output string.
+    /// Pops the address and length of a string and returns its text.
+    fn pop_string(&mut self) -> Result<String, OperationError> {
+        let len = self.context.pop()?;
+        let address = self.context.pop()?;
+        self.context.data_space.read_string(address, len)
+    }
+
+    /// Runs the file at `path`, looked up first in the directory of the including source
+    /// (or the current directory) and then in every directory of the search path.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file as written in the source.
+    /// * `once` - Whether to skip the file if it was already included.
+    /// * `including_source` - Name of the source that includes the file, if any.
+    ///
+    /// # Returns
+    /// * Whether any token of the file failed.
+    /// * `Err(OperationError::FailReadFile)` if the file cannot be found or read.
+    /// * `Err(OperationError::IncludeCycle)` if the file is already being included.
+    fn include_file(
+        &mut self,
+        path: &str,
+        once: bool,
+        including_source: Option<&str>,
+    ) -> Result<bool, OperationError> {
+        let base = including_source
+            .and_then(|source| Path::new(source).parent())
+            .unwrap_or(Path::new(""));
+        let resolved = std::iter::once(base)
+            .chain(self.search_path.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(path))
+            .find(|candidate| candidate.is_file())
+            .ok_or(OperationError::FailReadFile)?;
+        let canonical = fs::canonicalize(&resolved).map_err(|_| OperationError::FailReadFile)?;
+        if once && self.included_files.contains(&canonical) {
+            return Ok(false);
+        }
+        let depth = self.include_stack.len();
+        let current = including_source.and_then(|source| fs::canonicalize(source).ok());
+        let outermost = (depth == 0).then_some(current).flatten();
+        if outermost.as_ref() == Some(&canonical) || self.include_stack.contains(&canonical) {
+            return Err(OperationError::IncludeCycle);
+        }
+        let content =
+            file_manager::read_to_string(&resolved).map_err(|_| OperationError::FailReadFile)?;
+        self.included_files.push(canonical.clone());
+
+        let included_source_name = resolved.to_string_lossy().to_string();
+        let source_name = std::mem::replace(&mut self.source_name, included_source_name);
+        self.include_stack.extend(outermost);
+        self.include_stack.push(canonical);
+        let failed = self.run_tokens(file_manager::tokenize_with_positions(&content));
+        self.include_stack.truncate(depth);
+        self.source_name = source_name;
+        Ok(failed)
+    }
+
+    /// Records an error and writes its message to the error output, or to the
+    /// program output when errors are interleaved.
+    ///
+    /// Short messages for errors inside an included file, or about including one, start
+    /// with the file and the line, since the message alone does not say where they are.
     ///
     /// # Arguments
     ///
-    /// * `output` - The output string where the error message will be appended.
     /// * `error` - The error that occurred.
-    fn add_string_output_error(&mut self, output: &mut String, error: OperationError) {
-        output.push_str(&format!("{}", error));
+    fn add_string_output_error(&mut self, error: ForthError) {
+        let in_included_file = !self.include_stack.is_empty() || error.error.is_include_error();
+        let message = match self.error_format {
+            ErrorFormat::Short if in_included_file => error.format_located(),
+            error_format => error.format(error_format),
+        };
+        let sink = self
+            .error_output
+            .as_mut()
+            .unwrap_or(&mut self.context.output);
+        let _ = sink.write_str(&message);
+        self.errors.push(error);
+    }
+
+    /// Builds a `ForthError` for an error raised while handling the top-level `token`.
+    ///
+    /// If the error was raised inside a user word, the innermost failing token and the
+    /// chain of words recorded while unwinding are used instead of the top-level token.
+    fn locate_error(&mut self, error: OperationError, token: &SourceToken) -> ForthError {
+        let (failing_token, call_trace) = self
+            .failure
+            .take()
+            .unwrap_or_else(|| (token.text.clone(), self.call_trace.clone()));
+        ForthError {
+            error,
+            token: Some(strip_version_suffix(&failing_token).to_string()),
+            source: self.source_name.clone(),
+            position: Some(token.position),
+            call_trace: call_trace.into_iter().rev().collect(),
+        }
+    }
+
+    /// Remembers the innermost token that failed, together with the words being executed.
+    fn record_failure(&mut self, token: &str) {
+        if self.failure.is_none() {
+            self.failure = Some((token.to_string(), self.call_trace.clone()));
+        }
     }
 
     /// Pushes a number onto the stack.
@@ -237,162 +964,174 @@ impl ForthCalculator {
     ///
     /// * `number` - The number to be pushed onto the stack.
     fn push_number(&mut self, number: i16) -> Result<(), OperationError> {
-        if self.stack.len() == self.max_stack_size as usize {
-            return Err(OperationError::StackOverflow);
-        }
-
-        self.stack.push(number);
-        Ok(())
+        self.context.push(number)
     }
 
-    /// Processes the validated tokens, iterating through each token and handling it.
+    /// Processes the compiled tokens, iterating through each token and handling it.
     ///
-    /// This function processes a list of tokens, calling `process_single_token` for each one. If the processing of a token fails (returns an error),
-    /// it attempts to handle the token as a word lookup by calling `handle_word_lookup`.
+    /// Each token is executed with `execute_token`, and an `if ... then` runs as a whole
+    /// like inside a word. If it fails, the error is located at the token (or at the `if`)
+    /// and appended to the output, and the recovery policy decides which tokens run next.
+    /// Processing stops as soon as `bye` or `exit-code` halts the interpreter.
     ///
     /// # Arguments
-    /// * `tokens` - A slice of `String` containing the tokens to be processed.
-    /// * `output` - A mutable reference to a `String` where output will be written.
-    fn process_tokens(&mut self, tokens: &[String], output: &mut String) {
-        for token in tokens {
-            let result = self.process_single_token(token, output);
+    /// * `tokens` - A slice of `SourceToken` containing the tokens to be processed.
+    /// * `skipped_line` - The line being skipped after an error, if any.
+    ///
+    /// # Returns
+    /// `true` if any token failed.
+    fn process_tokens(&mut self, tokens: &[SourceToken], skipped_line: &mut Option<usize>) -> bool {
+        let mut failed = false;
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if *skipped_line == Some(token.position.line) {
+                i += 1;
+                continue;
+            }
+            let (result, next) = match token.text.as_str() {
+                "if" => {
+                    let texts: Vec<String> =
+                        tokens[i..].iter().map(|token| token.text.clone()).collect();
+                    let end = Conditional::get_branch_indices(&texts, 0)
+                        .map_or(tokens.len(), |(then_index, _)| i + then_index + 1);
+                    (self.execute_if_token(&texts, 0).map(|_| ()), end)
+                }
+                _ => (self.execute_token(&token.text), i + 1),
+            };
+            i = next;
             if let Err(error) = result {
-                let token_parts: Vec<&str> = token.split('_').collect();
-                if token_parts.len() > 1 && self.word_registry.contains_key(token_parts[0]) {
-                    self.handle_word_lookup(token, output);
+                if self.failure_reported {
+                    self.failure_reported = false;
+                    self.failure = None;
                 } else {
-                    self.add_string_output_error(output, error);
+                    let error = self.locate_error(error, token);
+                    self.add_string_output_error(error);
+                }
+                failed = true;
+                match self.recovery_policy {
+                    RecoveryPolicy::Halt => break,
+                    RecoveryPolicy::SkipLine => *skipped_line = Some(token.position.line),
+                    RecoveryPolicy::Continue => {}
                 }
             }
+            if self.context.halted.is_some() {
+                break;
+            }
         }
+        failed
     }
 
-    /// Processes a single token by attempting to parse it as a number or an operation.
-    ///
-    /// This function tries to parse the given token as a number. If it succeeds, the number is pushed onto the stack.
-    /// If it fails to parse the token as a number, it attempts to execute the token as an operation by calling `execute_operation`.
+    /// Executes a single token and updates the execution statistics.
     ///
     /// # Arguments
     /// * `token` - A reference to a `str` representing the token to be processed.
-    /// * `output` - A mutable reference to a `String` where output will be written.
     ///
-    fn process_single_token(
-        &mut self,
-        token: &str,
-        output: &mut String,
-    ) -> Result<(), OperationError> {
-        match token.parse::<i16>() {
-            Ok(number) => self.push_number(number),
-            Err(_) => self.execute_operation(token, output),
+    fn execute_token(&mut self, token: &str) -> Result<(), OperationError> {
+        self.stats.tokens_executed += 1;
+        if let Some(trace_output) = &mut self.trace_output {
+            let line = format!(
+                "{}{} ( {} )\n",
+                "  ".repeat(self.call_trace.len()),
+                strip_version_suffix(token),
+                file_manager::format_stack(&self.context.stack)
+            );
+            trace_output.write_str(&line)?;
         }
+        let result = self.dispatch_token(token);
+        self.stats.max_stack_depth = self.stats.max_stack_depth.max(self.context.stack.len());
+        result
     }
+
+    /// Runs a single token.
+    ///
+    /// Numbers are pushed onto the stack, `evaluate` runs the string on top of the stack,
+    /// tokens with the canonical suffix are run as predefined operations and tokens with
+    /// a version suffix run that version of a user-defined word. Any other token is an
+    /// unknown word and fails with `WordNotFound`.
+    fn dispatch_token(&mut self, token: &str) -> Result<(), OperationError> {
+        if let Ok(number) = token.parse::<i16>() {
+            return self.push_number(number);
+        }
+        // `evaluate` needs the whole interpreter, not only the context.
+        if token == EVALUATE_WORD {
+            return self.evaluate_string();
+        }
+        match token.rsplit_once('_') {
+            Some((_, CANONIC_SUBFIX)) => self.execute_operation(token),
+            Some((name, index)) => match index.parse::<usize>() {
+                Ok(word_index) if self.context.word_registry.has_version(name, word_index) => {
+                    self.execute_word_by_index(word_index)
+                }
+                _ => Err(OperationError::WordNotFound),
+            },
+            None => Err(OperationError::WordNotFound),
+        }
+    }
+
     /// Executes an operation based on the provided token.
     ///
     /// # Arguments
     ///
     /// * `token` - The string representation of the operation.
-    /// * `output` - A mutable reference to the output string (used for operations that produce output).
-    fn execute_operation(
-        &mut self,
-        token: &str,
-        output: &mut String,
-    ) -> Result<(), OperationError> {
-        let token_parts: Vec<&str> = token.split('_').collect();
-        if token_parts.len() > 1 && token_parts[1] == CANONIC_SUBFIX {
-            let original_token = token_parts[0];
+    fn execute_operation(&mut self, token: &str) -> Result<(), OperationError> {
+        if let Some((original_token, CANONIC_SUBFIX)) = token.rsplit_once('_') {
             let operation_type =
                 OperationType::from_token(original_token).ok_or(OperationError::WordNotFound)?;
-
             if let Some(operation) = self.operations.get(&operation_type) {
-                return operation.apply(&mut self.stack);
-            }
-
-            if let Some(operation) = self.output_operations.get(&operation_type) {
-                return operation.apply(&mut self.stack, output, original_token);
+                self.context.token.clear();
+                self.context.token.push_str(original_token);
+                return operation.apply(&mut self.context);
             }
         }
         Err(OperationError::WordNotFound)
     }
 
-    /// Handles the execution of a token that may refer to a user-defined word.
-    /// If the word is not found in the registry, an error is appended to the output.
-    /// If the token ends in a valid index, it attempts to execute the corresponding word.
-    /// If the token is a standard operation, it attempts to execute it.
-    ///
-    /// # Parameters
-    /// - `token`: A string token that may refer to a user-defined word or an operation.
-    /// - `output`: A mutable reference to a string where error messages or results are written.
-    ///
-    fn handle_word_lookup(&mut self, token: &str, output: &mut String) {
-        let token_parts: Vec<&str> = token.split('_').collect();
-        let word_token = token_parts[0];
-        if !self.word_registry.contains_key(word_token) {
-            self.add_string_output_error(output, OperationError::WordNotFound);
-            return;
-        }
-
-        let index_word_token = token_parts[1].parse::<usize>();
-        if index_word_token.is_err() {
-            if let Err(error) = self.execute_operation(token, output) {
-                self.add_string_output_error(output, error);
-            }
-        } else if let Ok(wi) = index_word_token {
-            if let Err(error) = self.execute_word_by_index(wi, word_token, output) {
-                self.add_string_output_error(output, error);
-            }
-        }
-    }
-
     /// Executes a word by its index from the word registry and processes the associated tokens.
     ///
-    /// # Parameters
-    /// - `word_index`: The index of the word in the word registry.
-    /// - `_token`: The original token (not used here but kept for signature compatibility).
-    /// - `output`: A mutable reference to a string where error messages or results are written.
-    ///
-    fn execute_word_by_index(
-        &mut self,
-        word_index: usize,
-        _token: &str,
-        output: &mut String,
-    ) -> Result<(), OperationError> {
-        let tokens_to_process = self.get_word_tokens(word_index);
-        self.process_word_tokens(&tokens_to_process, output)
-    }
-
-    /// Retrieves the tokens that represent the body of a word definition.
+    /// Native words are applied directly to the context, and fail with `WordNotFound`
+    /// while their implementation is not registered. For the others, the name of the
+    /// word is kept in the call trace while its body runs.
     ///
     /// # Parameters
     /// - `word_index`: The index of the word in the word registry.
     ///
-    /// # Returns
-    /// Returns a vector of strings representing the tokens of the word body.
-    ///
-    fn get_word_tokens(&self, word_index: usize) -> Vec<String> {
-        self.word_registry.words[word_index]
-            .body
-            .iter()
-            .map(|s| s.to_string())
-            .collect()
+    fn execute_word_by_index(&mut self, word_index: usize) -> Result<(), OperationError> {
+        self.stats.words_called += 1;
+        let word = &self.context.word_registry.words[word_index];
+        match &word.definition {
+            Definition::Body(body) => {
+                let tokens_to_process = body.clone();
+                self.call_trace.push(word.name.clone());
+                let result = self.process_word_tokens(&tokens_to_process);
+                self.call_trace.pop();
+                result
+            }
+            Definition::Native(native) => {
+                let native = Rc::clone(native);
+                self.context.token.clone_from(&word.name);
+                native.apply(&mut self.context)
+            }
+            Definition::UnboundNative => Err(OperationError::WordNotFound),
+        }
     }
 
     /// Execute the if token
     ///
     /// # Returns
     /// The position of the "then"
-    fn execute_if_token(
-        &mut self,
-        tokens: &[String],
-        output: &mut String,
-        i: usize,
-    ) -> Result<usize, OperationError> {
-        let cond = self.stack.pop().ok_or(OperationError::StackUnderflow)?;
+    fn execute_if_token(&mut self, tokens: &[String], i: usize) -> Result<usize, OperationError> {
+        let cond = self
+            .context
+            .stack
+            .pop()
+            .ok_or(OperationError::StackUnderflow)?;
         let (then_pos, if_branch, else_branch) = Conditional::extract_branch(tokens, i)?;
 
         if cond != 0 {
-            self.process_word_tokens(if_branch, output)?;
+            self.process_word_tokens(if_branch)?;
         } else if let Some(else_body) = else_branch {
-            self.process_word_tokens(else_body, output)?;
+            self.process_word_tokens(else_body)?;
         }
 
         Ok(then_pos + 1)
@@ -402,38 +1141,20 @@ impl ForthCalculator {
     ///
     /// # Parameters
     /// - `tokens`: A slice of strings representing the body of a word to be processed.
-    /// - `output`: A mutable reference to a string where error messages or results are written.
     ///    
-    fn process_word_tokens(
-        &mut self,
-        tokens: &[String],
-        output: &mut String,
-    ) -> Result<(), OperationError> {
+    fn process_word_tokens(&mut self, tokens: &[String]) -> Result<(), OperationError> {
         let mut i = 0;
         while i < tokens.len() {
-            match tokens[i].as_str() {
-                "if" => {
-                    i = self.execute_if_token(tokens, output, i)?;
-                }
-                token => {
-                    if let Ok(number) = token.parse::<i16>() {
-                        self.push_number(number)?;
-                    } else {
-                        let word_token_parts: Vec<&str> = token.split("_").collect();
-                        let word_token_name = word_token_parts[0];
-                        if word_token_parts[1] != CANONIC_SUBFIX {
-                            let index_word_token = word_token_parts[1].parse::<usize>();
-                            if !self.word_registry.contains_key(word_token_name) {
-                                return Err(OperationError::WordNotFound);
-                            }
-                            if let Ok(wi) = index_word_token {
-                                self.execute_word_by_index(wi, word_token_name, output)?;
-                            }
-                        } else {
-                            self.execute_operation(token, output)?;
-                        }
-                    }
-                    i += 1;
+            let result = match tokens[i].as_str() {
+                "if" => self.execute_if_token(tokens, i),
+                token => self.execute_token(token).map(|_| i + 1),
+            };
+            match result {
+                Ok(_) if self.context.halted.is_some() => break,
+                Ok(next) => i = next,
+                Err(error) => {
+                    self.record_failure(&tokens[i]);
+                    return Err(error);
                 }
             }
         }
@@ -441,6 +1162,99 @@ impl ForthCalculator {
     }
 }
 
+/// Lowercases a token, except for the `."`, `.(` and string literals, whose text keeps
+/// its case.
+fn normalize_token(token: &str) -> String {
+    if file_manager::is_literal(token) {
+        token.to_string()
+    } else {
+        token.to_lowercase()
+    }
+}
+
+/// Removes the version suffix (`_c` or `_<index>`) that is appended to tokens during
+/// word extraction, giving back the token as the user wrote it.
+fn strip_version_suffix(token: &str) -> &str {
+    match token.rsplit_once('_') {
+        Some((name, suffix)) if suffix == CANONIC_SUBFIX || suffix.parse::<usize>().is_ok() => name,
+        _ => token,
+    }
+}
+
+/// Checks if `token` is `include`, `require` or `included`.
+fn is_include_directive(token: &SourceToken) -> bool {
+    matches!(
+        token.text.to_lowercase().as_str(),
+        INCLUDE_DIRECTIVE | REQUIRE_DIRECTIVE | INCLUDED_DIRECTIVE
+    )
+}
+
+/// Returns where the first part of `tokens` that can be compiled on its own ends: at
+/// the first `include`, `require` or `included` outside a word definition and a
+/// conditional, right after the first `evaluate` outside them, or at the end of the tokens.
+fn segment_end(tokens: &[SourceToken]) -> usize {
+    let mut in_definition = false;
+    let mut branch_nesting = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        let top_level = !in_definition && branch_nesting == 0;
+        match token.text.to_lowercase().as_str() {
+            ":" => in_definition = true,
+            ";" => in_definition = false,
+            "if" if !in_definition => branch_nesting += 1,
+            "then" if !in_definition && branch_nesting > 0 => branch_nesting -= 1,
+            EVALUATE_WORD if top_level => return i + 1,
+            _ if top_level && is_include_directive(token) => return i,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Returns how many of the leading `tokens` form complete top-level constructs.
+///
+/// A construct is incomplete while a `:` definition or a top-level `if` has not been
+/// closed, while an `include` or `require` is missing its file name, or when its last
+/// token reaches the end of the input: a word could continue in the next chunk, and a
+/// literal that is not closed yet could be closed there.
+///
+/// # Arguments
+/// * `tokens` - The tokens of the input.
+/// * `input_len` - The number of characters in the input.
+fn complete_tokens_len(tokens: &[SourceToken], input_len: usize) -> usize {
+    let mut in_definition = false;
+    let mut branch_nesting = 0;
+    let mut awaiting_file_name = false;
+    let mut complete = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.span.end == input_len {
+            let closed_literal = file_manager::is_literal(&token.text)
+                && !file_manager::is_unterminated_literal(&token.text);
+            if !closed_literal {
+                break;
+            }
+        }
+        if awaiting_file_name {
+            awaiting_file_name = false;
+        } else {
+            match token.text.to_lowercase().as_str() {
+                ":" => in_definition = true,
+                ";" => in_definition = false,
+                "if" if !in_definition => branch_nesting += 1,
+                "then" if !in_definition && branch_nesting > 0 => branch_nesting -= 1,
+                INCLUDE_DIRECTIVE | REQUIRE_DIRECTIVE if !in_definition => {
+                    awaiting_file_name = true
+                }
+                _ => {}
+            }
+        }
+        if !in_definition && branch_nesting == 0 && !awaiting_file_name {
+            complete = i + 1;
+        }
+    }
+    complete
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -462,7 +1276,7 @@ mod tests {
         let result = calc.extract_words(&mut tokens);
         assert!(result.is_ok());
         assert_eq!(tokens.len(), 0);
-        assert!(calc.word_registry.contains_key("doble"));
+        assert!(calc.context.word_registry.contains_key("doble"));
     }
 
     #[test]
@@ -484,8 +1298,8 @@ mod tests {
 
         let result = calc.extract_words(&mut tokens);
         assert!(result.is_ok());
-        assert!(calc.word_registry.contains_key("cuadrado"));
-        assert!(calc.word_registry.contains_key("cuatro"));
+        assert!(calc.context.word_registry.contains_key("cuadrado"));
+        assert!(calc.context.word_registry.contains_key("cuatro"));
         assert_eq!(tokens.len(), 0);
     }
 
@@ -556,9 +1370,9 @@ mod tests {
 
         let result = calc.extract_words(&mut tokens);
         assert!(result.is_ok());
-        assert!(calc.word_registry.contains_key("x"));
+        assert!(calc.context.word_registry.contains_key("x"));
 
-        let versions = calc.word_registry.get_word_versions("x").unwrap();
+        let versions = calc.context.word_registry.get_word_versions("x").unwrap();
         assert_eq!(versions.len(), 2);
         assert_eq!(tokens.len(), 0);
     }
@@ -0,0 +1,291 @@
+use crate::core::error::{ErrorFormat, OperationError, RecoveryPolicy};
+use std::path::PathBuf;
+
+/// Bytes every image file starts with.
+const IMAGE_MAGIC: &[u8; 4] = b"RTFI";
+
+/// Version of the image format written by `Image::encode`.
+pub const IMAGE_VERSION: u16 = 1;
+
+/// A word definition stored in an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageWord {
+    /// The name of the word.
+    pub name: String,
+    /// The already tokenized body of the word, or `None` for a native word, whose
+    /// Rust implementation has to be registered again after restoring the image.
+    pub body: Option<Vec<String>>,
+}
+
+/// A snapshot of the whole interpreter state.
+///
+/// The snapshot keeps every version of every word, in definition order, with its body
+/// already tokenized, so restoring it does not need to process any source again.
+///
+/// Images are stored in a binary format: the magic bytes `RTFI`, the format version
+/// and then every field in declaration order, with little-endian integers and
+/// length-prefixed strings and lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Maximum allowed stack size.
+    pub max_stack_size: i16,
+    /// The data stack, from bottom to top.
+    pub stack: Vec<i16>,
+    /// The bytes of the data space, where the string literals of the words are stored.
+    pub data_space: Vec<u8>,
+    /// Every word definition, including the shadowed versions.
+    pub words: Vec<ImageWord>,
+    /// How errors are rendered.
+    pub error_format: ErrorFormat,
+    /// What happens with the remaining tokens after one fails.
+    pub recovery_policy: RecoveryPolicy,
+    /// Whether failed runs are rolled back.
+    pub transactional: bool,
+    /// Name of the source used in detailed error messages.
+    pub source_name: String,
+    /// Canonical paths of the files included so far, which `require` does not load again.
+    pub included_files: Vec<PathBuf>,
+}
+
+impl Image {
+    /// Serializes the image in the current format version.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.bytes.extend_from_slice(IMAGE_MAGIC);
+        encoder
+            .bytes
+            .extend_from_slice(&IMAGE_VERSION.to_le_bytes());
+
+        encoder
+            .bytes
+            .extend_from_slice(&self.max_stack_size.to_le_bytes());
+        encoder.len(self.stack.len());
+        for value in &self.stack {
+            encoder.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        encoder.len(self.data_space.len());
+        encoder.bytes.extend_from_slice(&self.data_space);
+        encoder.len(self.words.len());
+        for word in &self.words {
+            encoder.string(&word.name);
+            match &word.body {
+                Some(body) => {
+                    encoder.bytes.push(0);
+                    encoder.strings(body);
+                }
+                None => encoder.bytes.push(1),
+            }
+        }
+        encoder.bytes.push(match self.error_format {
+            ErrorFormat::Short => 0,
+            ErrorFormat::Detailed => 1,
+        });
+        encoder.bytes.push(match self.recovery_policy {
+            RecoveryPolicy::Halt => 0,
+            RecoveryPolicy::SkipLine => 1,
+            RecoveryPolicy::Continue => 2,
+        });
+        encoder.bytes.push(self.transactional as u8);
+        encoder.string(&self.source_name);
+        let included_files: Vec<String> = self
+            .included_files
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        encoder.strings(&included_files);
+        encoder.bytes
+    }
+
+    /// Deserializes an image written by `encode`.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::InvalidImage)` if the bytes are not an image, the image is
+    ///   truncated or corrupted, or it was written with an unsupported format version.
+    pub fn decode(bytes: &[u8]) -> Result<Image, OperationError> {
+        let mut decoder = Decoder { bytes, position: 0 };
+        if decoder.take(IMAGE_MAGIC.len())? != IMAGE_MAGIC {
+            return Err(OperationError::InvalidImage);
+        }
+        if u16::from_le_bytes(decoder.array()?) != IMAGE_VERSION {
+            return Err(OperationError::InvalidImage);
+        }
+
+        let max_stack_size = i16::from_le_bytes(decoder.array()?);
+        let stack = (0..decoder.len()?)
+            .map(|_| decoder.array().map(i16::from_le_bytes))
+            .collect::<Result<Vec<i16>, OperationError>>()?;
+        let data_space_len = decoder.len()?;
+        if data_space_len > i16::MAX as usize {
+            return Err(OperationError::InvalidImage);
+        }
+        let data_space = decoder.take(data_space_len)?.to_vec();
+        let words = (0..decoder.len()?)
+            .map(|_| {
+                let name = decoder.string()?;
+                let body = match decoder.byte()? {
+                    0 => Some(decoder.strings()?),
+                    1 => None,
+                    _ => return Err(OperationError::InvalidImage),
+                };
+                Ok(ImageWord { name, body })
+            })
+            .collect::<Result<Vec<ImageWord>, OperationError>>()?;
+        let error_format = match decoder.byte()? {
+            0 => ErrorFormat::Short,
+            1 => ErrorFormat::Detailed,
+            _ => return Err(OperationError::InvalidImage),
+        };
+        let recovery_policy = match decoder.byte()? {
+            0 => RecoveryPolicy::Halt,
+            1 => RecoveryPolicy::SkipLine,
+            2 => RecoveryPolicy::Continue,
+            _ => return Err(OperationError::InvalidImage),
+        };
+        let transactional = match decoder.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(OperationError::InvalidImage),
+        };
+        let source_name = decoder.string()?;
+        let included_files = decoder.strings()?.into_iter().map(PathBuf::from).collect();
+
+        if decoder.position != bytes.len() || stack.len() > max_stack_size.max(0) as usize {
+            return Err(OperationError::InvalidImage);
+        }
+        Ok(Image {
+            max_stack_size,
+            stack,
+            data_space,
+            words,
+            error_format,
+            recovery_policy,
+            transactional,
+            source_name,
+            included_files,
+        })
+    }
+}
+
+/// Appends the values of an image to a byte buffer.
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn len(&mut self, len: usize) {
+        self.bytes.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+
+    fn string(&mut self, string: &str) {
+        self.len(string.len());
+        self.bytes.extend_from_slice(string.as_bytes());
+    }
+
+    fn strings(&mut self, strings: &[String]) {
+        self.len(strings.len());
+        for string in strings {
+            self.string(string);
+        }
+    }
+}
+
+/// Reads the values of an image from a byte slice.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], OperationError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(OperationError::InvalidImage)?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], OperationError> {
+        self.take(N)?
+            .try_into()
+            .map_err(|_| OperationError::InvalidImage)
+    }
+
+    fn byte(&mut self) -> Result<u8, OperationError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, OperationError> {
+        Ok(u32::from_le_bytes(self.array()?) as usize)
+    }
+
+    fn string(&mut self) -> Result<String, OperationError> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| OperationError::InvalidImage)
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, OperationError> {
+        (0..self.len()?).map(|_| self.string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_image() -> Image {
+        Image {
+            max_stack_size: 64,
+            stack: vec![1, -2, 300],
+            data_space: b"hello".to_vec(),
+            words: vec![
+                ImageWord {
+                    name: "square".to_string(),
+                    body: Some(vec!["dup_c".to_string(), "*_c".to_string()]),
+                },
+                ImageWord {
+                    name: "double".to_string(),
+                    body: None,
+                },
+            ],
+            error_format: ErrorFormat::Detailed,
+            recovery_policy: RecoveryPolicy::SkipLine,
+            transactional: true,
+            source_name: "lib.fth".to_string(),
+            included_files: vec![PathBuf::from("/usr/share/forth/lib.fth")],
+        }
+    }
+
+    #[test]
+    fn test_encode_and_decode_round_trip() {
+        let image = sample_image();
+        assert_eq!(Image::decode(&image.encode()), Ok(image));
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_images() {
+        let bytes = sample_image().encode();
+
+        assert_eq!(Image::decode(b"stack"), Err(OperationError::InvalidImage));
+        assert_eq!(
+            Image::decode(&bytes[..bytes.len() - 1]),
+            Err(OperationError::InvalidImage)
+        );
+
+        for version in [0, 99] {
+            let mut other_version = bytes.clone();
+            other_version[4] = version;
+            assert_eq!(
+                Image::decode(&other_version),
+                Err(OperationError::InvalidImage)
+            );
+        }
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert_eq!(Image::decode(&trailing), Err(OperationError::InvalidImage));
+    }
+}
@@ -7,6 +7,31 @@
 /// runtime stack operations, file handling, and general interpreter failures.
 pub mod error;
 
+/// Defines the state shared by the interpreter with every operation.
+///
+/// This module provides the `ExecutionContext` struct, which groups the stack, the output
+/// buffer, the current token and the user-defined words so that every operation receives
+/// them through a single value.
+pub mod execution_context;
+
+/// Defines the memory that Forth code can address.
+///
+/// This module provides the `DataSpace` struct, a growing array of bytes where strings
+/// such as the command-line arguments are stored and referred to by address and length.
+pub mod data_space;
+
+/// Defines where the text produced by the interpreter goes.
+///
+/// This module provides the `OutputSink` enum, which either keeps the output in memory
+/// or streams it to a writer or a callback as soon as it is produced.
+pub mod output_sink;
+
+/// Defines the snapshot of the whole interpreter state and its file format.
+///
+/// This module provides the `Image` struct, which holds the stack, every word definition
+/// and the settings of a `ForthCalculator`, and encodes it in a versioned binary format.
+pub mod image;
+
 /// Implements the core logic of the Forth interpreter.
 ///
 /// This module provides the `ForthCalculator` struct, which is responsible for parsing
@@ -23,6 +48,18 @@ pub mod forth_calculator;
 /// in a structured map for execution.
 pub mod operation;
 
+/// Defines the structured result returned when evaluating Forth code.
+///
+/// This module provides the `RunResult` struct, which gathers the final stack, the output,
+/// the errors, the words defined and the `ExecutionStats` of an evaluation.
+pub mod run_result;
+
+/// Defines where the stack is saved once an evaluation finishes.
+///
+/// This module provides the `StackPersistence` enum, which can disable saving or write the
+/// stack to a file at a chosen path or to any writer.
+pub mod stack_persistence;
+
 /// Module containing type aliases used for control structure handling.
 ///
 /// This file defines type aliases that are used to manage the results of operations
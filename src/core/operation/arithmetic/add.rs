@@ -1,4 +1,5 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 /// Represents the addition operation (`+`).
@@ -9,13 +10,14 @@ use crate::core::operation::Operation;
 pub struct Add;
 
 impl Operation for Add {
-    /// Applies the addition operation to the provided stack.
+    /// Applies the addition operation to the stack of the provided context.
     ///
     /// # Arguments
     ///
-    /// * `stack` - A mutable reference to a stack of 16-bit integers.
+    /// * `context` - The execution context whose stack of 16-bit integers is modified.
     ///
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         let a = stack.pop().ok_or(OperationError::StackUnderflow)?;
         let b = stack.pop().ok_or(OperationError::StackUnderflow)?;
         stack.push(a + b);
@@ -25,32 +27,32 @@ impl Operation for Add {
 
 #[test]
 fn test_add_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Add.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![5]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Add.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![5]);
 }
 
 #[test]
 fn test_add_last_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3, 3];
-    Add.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 6]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 3]);
+    Add.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 6]);
 }
 
 #[test]
 fn test_add_numbers_multiple_times() {
-    let mut stack: Vec<i16> = vec![2, 3, 3];
-    Add.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 6]);
-    Add.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![8]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 3]);
+    Add.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 6]);
+    Add.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![8]);
 }
 
 #[test]
 fn test_add_stack_underflow() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Add.apply(&mut stack),
+        Add.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,4 +1,5 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 /// Represents the division operation (`/`).
@@ -15,18 +16,19 @@ use crate::core::operation::Operation;
 pub struct Div;
 
 impl Operation for Div {
-    /// Applies the division operation to the provided stack.
+    /// Applies the division operation to the stack of the provided context.
     ///
     /// # Arguments
     ///
-    /// * `stack` - A mutable reference to a stack of 16-bit integers.
+    /// * `context` - The execution context whose stack of 16-bit integers is modified.
     ///
     /// # Returns
     ///
     /// * `Ok(())` if the operation is successful.
     /// * `Err(OperationError::StackUnderflow)` if the stack has fewer than two elements.
     /// * `Err(OperationError::DivisionByZero)` if the divisor is zero.
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         let divisor = stack.pop().ok_or(OperationError::StackUnderflow)?;
         let dividen: i16 = stack.pop().ok_or(OperationError::StackUnderflow)?;
         if divisor == 0 {
@@ -39,39 +41,39 @@ impl Operation for Div {
 
 #[test]
 fn test_divide_normal() {
-    let mut stack: Vec<i16> = vec![6, 2];
-    Div.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![3]);
+    let mut context = ExecutionContext::with_stack(vec![6, 2]);
+    Div.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![3]);
 }
 
 #[test]
 fn test_divide_last_two_numbers() {
-    let mut stack: Vec<i16> = vec![6, 4, 2];
-    Div.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![6, 2]);
+    let mut context = ExecutionContext::with_stack(vec![6, 4, 2]);
+    Div.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![6, 2]);
 }
 
 #[test]
 fn test_div_numbers_multiple_times() {
-    let mut stack: Vec<i16> = vec![2, 6, 3];
-    Div.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 2]);
-    Div.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 6, 3]);
+    Div.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 2]);
+    Div.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![1]);
 }
 
 #[test]
 fn test_truncate_to_zero_divide() {
-    let mut stack: Vec<i16> = vec![2, 4];
-    Div.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![2, 4]);
+    Div.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
 }
 
 #[test]
 fn test_divide_by_zero() {
-    let mut stack: Vec<i16> = vec![1, 0];
+    let mut context = ExecutionContext::with_stack(vec![1, 0]);
     assert!(matches!(
-        Div.apply(&mut stack),
+        Div.apply(&mut context),
         Err(OperationError::DivisionByZero)
     ));
 }
@@ -1,4 +1,5 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 /// Represents the multiplication operation (`*`).
@@ -9,12 +10,13 @@ use crate::core::operation::Operation;
 pub struct Mul;
 
 impl Operation for Mul {
-    /// Applies the multiplication operation to the provided stack.
+    /// Applies the multiplication operation to the stack of the provided context.
     ///
     /// # Arguments
     ///
-    /// * `stack` - A mutable reference to a stack of 16-bit integers.    
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    /// * `context` - The execution context whose stack of 16-bit integers is modified.    
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         let a = stack.pop().ok_or(OperationError::StackUnderflow)?;
         let b = stack.pop().ok_or(OperationError::StackUnderflow)?;
         stack.push(a * b);
@@ -24,32 +26,32 @@ impl Operation for Mul {
 
 #[test]
 fn test_mul_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Mul.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![6]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Mul.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![6]);
 }
 
 #[test]
 fn test_mul_last_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3, 1];
-    Mul.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 3]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 1]);
+    Mul.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3]);
 }
 
 #[test]
 fn test_mul_numbers_multiple_times() {
-    let mut stack: Vec<i16> = vec![2, 4, 3];
-    Mul.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 12]);
-    Mul.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![24]);
+    let mut context = ExecutionContext::with_stack(vec![2, 4, 3]);
+    Mul.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 12]);
+    Mul.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![24]);
 }
 
 #[test]
 fn test_mul_stack_underflow() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Mul.apply(&mut stack),
+        Mul.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,4 +1,5 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 /// Represents the subtraction operation (`-`).
@@ -10,13 +11,14 @@ use crate::core::operation::Operation;
 pub struct Sub;
 
 impl Operation for Sub {
-    /// Applies the subtraction operation to the provided stack.
+    /// Applies the subtraction operation to the stack of the provided context.
     ///
     /// # Arguments
     ///
-    /// * `stack` - A mutable reference to a stack of 16-bit integers.
+    /// * `context` - The execution context whose stack of 16-bit integers is modified.
     ///    
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         let a = stack.pop().ok_or(OperationError::StackUnderflow)?;
         let b = stack.pop().ok_or(OperationError::StackUnderflow)?;
         stack.push(b - a);
@@ -26,32 +28,32 @@ impl Operation for Sub {
 
 #[test]
 fn test_sub_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Sub.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Sub.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 
 #[test]
 fn test_sub_last_two_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3, 1];
-    Sub.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 2]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 1]);
+    Sub.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 2]);
 }
 
 #[test]
 fn test_sub_numbers_multiple_times() {
-    let mut stack: Vec<i16> = vec![2, 4, 3];
-    Sub.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 1]);
-    Sub.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 4, 3]);
+    Sub.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 1]);
+    Sub.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![1]);
 }
 
 #[test]
 fn test_sub_stack_underflow() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Sub.apply(&mut stack),
+        Sub.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
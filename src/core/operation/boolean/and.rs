@@ -1,10 +1,12 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 pub struct And;
 
 impl Operation for And {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -18,37 +20,37 @@ impl Operation for And {
 
 #[test]
 fn test_and_numbers() {
-    let mut stack: Vec<i16> = vec![-1, -1];
-    And.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![-1, -1]);
+    And.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 #[test]
 fn test_not_and_number() {
-    let mut stack: Vec<i16> = vec![-1, 0];
-    And.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
-    let mut stack_two: Vec<i16> = vec![0, 0];
-    And.apply(&mut stack_two).unwrap();
-    assert_eq!(stack_two, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![-1, 0]);
+    And.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
+    let mut context_two = ExecutionContext::with_stack(vec![0, 0]);
+    And.apply(&mut context_two).unwrap();
+    assert_eq!(context_two.stack, vec![0]);
 }
 #[test]
 fn test_and_numbers_many_elements() {
-    let mut stack: Vec<i16> = vec![2, -1, -1];
-    And.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, -1]);
+    let mut context = ExecutionContext::with_stack(vec![2, -1, -1]);
+    And.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, -1]);
 }
 
 #[test]
 fn test_underflow_and() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        And.apply(&mut stack),
+        And.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![1];
+    let mut second_context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        And.apply(&mut second_stack),
+        And.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
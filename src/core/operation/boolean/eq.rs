@@ -1,10 +1,12 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 pub struct Eq;
 
 impl Operation for Eq {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -18,34 +20,34 @@ impl Operation for Eq {
 
 #[test]
 fn test_eq_numbers() {
-    let mut stack: Vec<i16> = vec![3, 3];
-    Eq.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![3, 3]);
+    Eq.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 #[test]
 fn test_not_eq_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Eq.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Eq.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
 }
 #[test]
 fn test_eq_numbers_many_elements() {
-    let mut stack: Vec<i16> = vec![2, 3, 3];
-    Eq.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, -1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 3]);
+    Eq.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, -1]);
 }
 
 #[test]
 fn test_underflow_eq() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Eq.apply(&mut stack),
+        Eq.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![1];
+    let mut second_context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Eq.apply(&mut second_stack),
+        Eq.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
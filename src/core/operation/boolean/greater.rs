@@ -1,10 +1,12 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 pub struct Greater;
 
 impl Operation for Greater {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -18,34 +20,34 @@ impl Operation for Greater {
 
 #[test]
 fn test_greater_numbers() {
-    let mut stack: Vec<i16> = vec![2, 1];
-    Greater.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 1]);
+    Greater.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 #[test]
 fn test_not_greater_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Greater.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Greater.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
 }
 #[test]
 fn test_greater_numbers_many_elements() {
-    let mut stack: Vec<i16> = vec![2, 4, 3];
-    Greater.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, -1]);
+    let mut context = ExecutionContext::with_stack(vec![2, 4, 3]);
+    Greater.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, -1]);
 }
 
 #[test]
 fn test_underflow_greater() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Greater.apply(&mut stack),
+        Greater.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![1];
+    let mut second_context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Greater.apply(&mut second_stack),
+        Greater.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,9 +1,11 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 pub struct Less;
 
 impl Operation for Less {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -17,34 +19,34 @@ impl Operation for Less {
 
 #[test]
 fn test_less_numbers() {
-    let mut stack: Vec<i16> = vec![1, 2];
-    Less.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![1, 2]);
+    Less.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 #[test]
 fn test_not_less_number() {
-    let mut stack: Vec<i16> = vec![3, 2];
-    Less.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![3, 2]);
+    Less.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
 }
 #[test]
 fn test_less_numbers_many_elements() {
-    let mut stack: Vec<i16> = vec![2, 4, 3];
-    Less.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 0]);
+    let mut context = ExecutionContext::with_stack(vec![2, 4, 3]);
+    Less.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 0]);
 }
 
 #[test]
 fn test_underflow_less() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Less.apply(&mut stack),
+        Less.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![1];
+    let mut second_context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Less.apply(&mut second_stack),
+        Less.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
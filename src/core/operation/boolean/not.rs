@@ -1,10 +1,12 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 pub struct Not;
 
 impl Operation for Not {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -17,30 +19,30 @@ impl Operation for Not {
 
 #[test]
 fn test_not_numbers() {
-    let mut stack: Vec<i16> = vec![10];
-    Not.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
+    let mut context = ExecutionContext::with_stack(vec![10]);
+    Not.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
 }
 #[test]
 fn test_not_multiple_times() {
-    let mut stack: Vec<i16> = vec![10];
-    Not.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![0]);
-    Not.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![-1]);
+    let mut context = ExecutionContext::with_stack(vec![10]);
+    Not.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0]);
+    Not.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![-1]);
 }
 #[test]
 fn test_not_numbers_many_elements() {
-    let mut stack: Vec<i16> = vec![2, -1, -1];
-    Not.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, -1, 0]);
+    let mut context = ExecutionContext::with_stack(vec![2, -1, -1]);
+    Not.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, -1, 0]);
 }
 
 #[test]
 fn test_underflow_not() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Not.apply(&mut stack),
+        Not.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -49,10 +49,8 @@ impl Conditional {
                         break;
                     }
                 }
-                "else" => {
-                    if branch_nesting == 1 && else_index.is_none() {
-                        else_index = Some(j);
-                    }
+                "else" if branch_nesting == 1 && else_index.is_none() => {
+                    else_index = Some(j);
                 }
                 _ => {}
             }
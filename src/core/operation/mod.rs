@@ -1,4 +1,5 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use std::collections::HashMap;
 /// The `arithmetic` module provides basic arithmetic operations
 /// such as addition, subtraction, multiplication, and division.
@@ -23,6 +24,17 @@ pub mod output;
 /// all of which implement the `Operation` trait.
 pub mod stack_manipulation;
 
+/// The `string` module provides the operations on strings and bytes stored in the
+/// data space, such as `type`, `count`, `c@`, `c!`, `cmove`, `fill`, `compare`,
+/// `search`, `/string`, `-trailing`, `blank` and `s+`, which appends two strings. String
+/// literals (`s"` and `s\"`) are stored in the data space when the code is read.
+pub mod string;
+
+/// The `system` module provides the operations that control the interpreter itself,
+/// such as `bye` and `exit-code`, which stop the execution early, `argc`, `arg` (or `argv`)
+/// and `next-arg`, which give access to the arguments passed to the program, and `getenv`.
+pub mod system;
+
 /// The `word_definition` module proves fundamental operations
 /// to define and manage words defined by the user.
 ///
@@ -32,41 +44,31 @@ pub mod word_definition;
 /// Provides static utilities to locate and extract conditional branches from a token list.
 pub mod conditional_module;
 
-/// Defines a trait for stack-based operations.
+/// Defines a trait for the operations executed by the interpreter.
 ///
-/// Implementors of this trait must define the `apply` method, which takes
-/// a mutable reference to a stack of `i16` values and performs an operation on it.
+/// Implementors of this trait must define the `apply` method, which receives the
+/// `ExecutionContext` of the interpreter. Through it an operation can modify the
+/// stack, write output or read the token that invoked it, so every builtin shares
+/// the same signature regardless of what it needs.
 ///
 /// # Errors
 /// Returns an `OperationError` if the operation fails.
 pub trait Operation {
-    /// Applies the operation to the given stack.
+    /// Applies the operation to the given execution context.
     ///
     /// # Arguments
-    /// * `stack` - A mutable reference to a vector of `i16` values representing the stack.
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError>;
+    /// * `context` - A mutable reference to the state of the interpreter.
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError>;
 }
 
-/// Defines a trait for operations that produce output.
-///
-/// These operations not only modify the stack but also generate output in
-/// a string buffer.
-///
-/// # Errors
-/// Returns an `OperationError` if the operation fails.
-pub trait OperationOutput {
-    /// Applies the operation to the given stack and modifies the output string.
-    ///
-    /// # Arguments
-    /// * `stack` - A mutable reference to a vector of `i16` values representing the stack.
-    /// * `string_output` - A mutable reference to a `String` where output will be stored.
-    /// * `text_to_print` - A string slice representing additional text to print.
-    fn apply(
-        &self,
-        stack: &mut Vec<i16>,
-        string_output: &mut String,
-        text_to_print: &str,
-    ) -> Result<(), OperationError>;
+/// Lets plain closures be used as operations, e.g. when registering native words.
+impl<F> Operation for F
+where
+    F: Fn(&mut ExecutionContext) -> Result<(), OperationError>,
+{
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        self(context)
+    }
 }
 
 /// Represents the different types of operations supported by the Forth interpreter.
@@ -92,13 +94,37 @@ pub enum OperationType {
     Dot,
     Cr,
     PrintText,
+    ImmediateText,
     Emit,
+    Bye,
+    ExitCode,
+    Argc,
+    Arg,
+    NextArg,
+    Getenv,
+    Type,
+    Count,
+    CFetch,
+    CStore,
+    Cmove,
+    Fill,
+    Blank,
+    Compare,
+    Search,
+    SlashString,
+    MinusTrailing,
+    Concat,
 }
 
 impl OperationType {
     pub fn from_token(token: &str) -> Option<Self> {
-        if token.starts_with(".\"") && token.chars().nth(2) == Some(' ') {
-            return Some(OperationType::PrintText);
+        if token.chars().nth(2).is_some_and(char::is_whitespace) {
+            if token.starts_with(".\"") {
+                return Some(OperationType::PrintText);
+            }
+            if token.starts_with(".(") {
+                return Some(OperationType::ImmediateText);
+            }
         }
         let token_lower = token.to_lowercase();
         match token_lower.as_str() {
@@ -123,6 +149,26 @@ impl OperationType {
             "." => Some(OperationType::Dot),
             "cr" => Some(OperationType::Cr),
             "emit" => Some(OperationType::Emit),
+            //System
+            "bye" => Some(OperationType::Bye),
+            "exit-code" => Some(OperationType::ExitCode),
+            "argc" => Some(OperationType::Argc),
+            "arg" | "argv" => Some(OperationType::Arg),
+            "next-arg" => Some(OperationType::NextArg),
+            "getenv" => Some(OperationType::Getenv),
+            //String
+            "type" => Some(OperationType::Type),
+            "count" => Some(OperationType::Count),
+            "c@" => Some(OperationType::CFetch),
+            "c!" => Some(OperationType::CStore),
+            "cmove" => Some(OperationType::Cmove),
+            "fill" => Some(OperationType::Fill),
+            "blank" => Some(OperationType::Blank),
+            "compare" => Some(OperationType::Compare),
+            "search" => Some(OperationType::Search),
+            "/string" => Some(OperationType::SlashString),
+            "-trailing" => Some(OperationType::MinusTrailing),
+            "s+" => Some(OperationType::Concat),
             _ => None,
         }
     }
@@ -130,7 +176,7 @@ impl OperationType {
 
 /// Retrieves all standard operations and returns them in a `HashMap`.
 ///
-/// This function aggregates different types of operations (e.g., arithmetic, boolean)
+/// This function aggregates different types of operations (e.g., arithmetic, boolean, output)
 /// and returns them as a collection where each `OperationType` is mapped
 /// to a boxed `Operation` trait object.
 ///
@@ -143,20 +189,8 @@ pub fn get_all_standar_operations() -> HashMap<OperationType, Box<dyn Operation>
     ops.extend(arithmetic::get_operations());
     ops.extend(boolean::get_operations());
     ops.extend(stack_manipulation::get_operations());
-    ops
-}
-
-/// Retrieves all output-related operations and returns them in a `HashMap`.
-///
-/// This function collects operations that produce output (e.g., printing),
-/// mapping each `OperationType` to a boxed `OperationOutput` trait object.
-///
-/// # Examples
-/// ```text
-/// let output_operations = get_output_operations();
-/// ```
-pub fn get_output_operations() -> HashMap<OperationType, Box<dyn OperationOutput>> {
-    let mut ops = HashMap::new();
     ops.extend(output::get_operations());
+    ops.extend(string::get_operations());
+    ops.extend(system::get_operations());
     ops
 }
@@ -1,39 +1,33 @@
 use crate::core::error::OperationError;
-use crate::core::operation::OperationOutput;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
 
 pub struct Cr;
 
-impl OperationOutput for Cr {
-    fn apply(
-        &self,
-        _stack: &mut Vec<i16>,
-        output: &mut String,
-        _text_to_print: &str,
-    ) -> Result<(), OperationError> {
-        output.push('\n');
+impl Operation for Cr {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        context.output.write_str("\n")?;
         Ok(())
     }
 }
 
 #[test]
 fn test_cr_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
 
-    Cr.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    assert_eq!(stack, vec![2, 3]);
-    assert_eq!(output, "\n");
+    Cr.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3]);
+    assert_eq!(context.output.as_str(), "\n");
 }
 
 #[test]
 fn test_cr_many_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3, 4, 5, 6];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 4, 5, 6]);
 
-    Cr.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Cr.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Cr.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Cr.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    assert_eq!(stack, vec![2, 3, 4, 5, 6]);
-    assert_eq!(output, "\n\n\n\n");
+    Cr.apply(&mut context).unwrap();
+    Cr.apply(&mut context).unwrap();
+    Cr.apply(&mut context).unwrap();
+    Cr.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3, 4, 5, 6]);
+    assert_eq!(context.output.as_str(), "\n\n\n\n");
 }
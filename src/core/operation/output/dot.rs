@@ -1,48 +1,41 @@
 use crate::core::error::OperationError;
-use crate::core::operation::OperationOutput;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
 
 pub struct Dot;
 
-impl OperationOutput for Dot {
-    fn apply(
-        &self,
-        stack: &mut Vec<i16>,
-        output: &mut String,
-        _text_to_print: &str,
-    ) -> Result<(), OperationError> {
-        let num = stack.pop().ok_or(OperationError::StackUnderflow)?;
-        output.push_str(&format!("{} ", num));
+impl Operation for Dot {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let num = context.stack.pop().ok_or(OperationError::StackUnderflow)?;
+        context.output.write_str(&format!("{} ", num))?;
         Ok(())
     }
 }
 
 #[test]
 fn test_print_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    let mut output = String::new();
-    Dot.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    assert_eq!(stack, vec![2]);
-    assert_eq!(output, "3 ");
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Dot.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2]);
+    assert_eq!(context.output.as_str(), "3 ");
 }
 
 #[test]
 fn test_print_many_numbers() {
-    let mut stack: Vec<i16> = vec![2, 3, 4, 5, 6];
-    let mut output = String::new();
-    Dot.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Dot.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Dot.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    Dot.apply(&mut stack, &mut output, &"".to_string()).unwrap();
-    assert_eq!(stack, vec![2]);
-    assert_eq!(output, "6 5 4 3 ");
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 4, 5, 6]);
+    Dot.apply(&mut context).unwrap();
+    Dot.apply(&mut context).unwrap();
+    Dot.apply(&mut context).unwrap();
+    Dot.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2]);
+    assert_eq!(context.output.as_str(), "6 5 4 3 ");
 }
 
 #[test]
 fn test_print_number_underflow() {
-    let mut stack: Vec<i16> = vec![];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Dot.apply(&mut stack, &mut output, &"".to_string()),
+        Dot.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,18 +1,14 @@
 use crate::core::error::OperationError;
-use crate::core::operation::OperationOutput;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
 
 pub struct Emit;
 
-impl OperationOutput for Emit {
-    fn apply(
-        &self,
-        stack: &mut Vec<i16>,
-        output: &mut String,
-        _text_to_print: &str,
-    ) -> Result<(), OperationError> {
-        let num = stack.pop().ok_or(OperationError::StackUnderflow)?;
+impl Operation for Emit {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let num = context.stack.pop().ok_or(OperationError::StackUnderflow)?;
         if let Some(ch) = char::from_u32(num as u32) {
-            output.push_str(&format!("{} ", ch));
+            context.output.write_str(&format!("{} ", ch))?;
             Ok(())
         } else {
             Err(OperationError::InvalidCharacter)
@@ -22,39 +18,30 @@ impl OperationOutput for Emit {
 
 #[test]
 fn test_emit_lower_case_number() {
-    let mut stack: Vec<i16> = vec![97];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![97]);
 
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, "a ");
+    Emit.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), "a ");
 }
 
 #[test]
 fn test_emit_upper_case_number() {
-    let mut stack: Vec<i16> = vec![65];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![65]);
 
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, "A ");
+    Emit.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), "A ");
 }
 #[test]
 
 fn test_emit_multiple_numbers() {
-    let mut stack: Vec<i16> = vec![68, 67, 66, 65];
-    let mut output = String::new();
-
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    Emit.apply(&mut stack, &mut output, &"".to_string())
-        .unwrap();
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, "A B C D ");
+    let mut context = ExecutionContext::with_stack(vec![68, 67, 66, 65]);
+
+    Emit.apply(&mut context).unwrap();
+    Emit.apply(&mut context).unwrap();
+    Emit.apply(&mut context).unwrap();
+    Emit.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), "A B C D ");
 }
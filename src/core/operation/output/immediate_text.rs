@@ -0,0 +1,38 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use crate::utils::file_manager;
+
+/// Prints the text of a `.( text)` token. Inside a word definition the text is not
+/// compiled into the word: it is printed when the definition is reached.
+pub struct ImmediateText;
+
+impl Operation for ImmediateText {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        match file_manager::parse_print_literal(&context.token) {
+            Some(text) => context.output.write_str(&text),
+            None => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_immediate_text_basic() {
+    let mut context = ExecutionContext::with_stack(vec![1]);
+
+    context.token = ".( hello world)".to_string();
+    ImmediateText.apply(&mut context).unwrap();
+
+    assert_eq!(context.stack, vec![1]);
+    assert_eq!(context.output.as_str(), "hello world");
+}
+
+#[test]
+fn test_immediate_text_keeps_backslashes() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+
+    context.token = ".( a\\nb)".to_string();
+    ImmediateText.apply(&mut context).unwrap();
+
+    assert_eq!(context.output.as_str(), "a\\nb");
+}
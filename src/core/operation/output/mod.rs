@@ -2,29 +2,29 @@ use std::collections::HashMap;
 pub mod cr;
 pub mod dot;
 pub mod emit;
+pub mod immediate_text;
 pub mod text;
 
 pub use cr::Cr;
 pub use dot::Dot;
 pub use emit::Emit;
+pub use immediate_text::ImmediateText;
 pub use text::PrintText;
 
-use super::{OperationOutput, OperationType};
+use super::{Operation, OperationType};
 
-pub fn get_operations() -> HashMap<OperationType, Box<dyn OperationOutput>> {
+pub fn get_operations() -> HashMap<OperationType, Box<dyn Operation>> {
     let mut ops = HashMap::new();
+    ops.insert(OperationType::Dot, Box::new(Dot) as Box<dyn Operation>);
+    ops.insert(OperationType::Cr, Box::new(Cr) as Box<dyn Operation>);
+    ops.insert(OperationType::Emit, Box::new(Emit) as Box<dyn Operation>);
     ops.insert(
-        OperationType::Dot,
-        Box::new(Dot) as Box<dyn OperationOutput>,
-    );
-    ops.insert(OperationType::Cr, Box::new(Cr) as Box<dyn OperationOutput>);
-    ops.insert(
-        OperationType::Emit,
-        Box::new(Emit) as Box<dyn OperationOutput>,
+        OperationType::PrintText,
+        Box::new(PrintText) as Box<dyn Operation>,
     );
     ops.insert(
-        OperationType::PrintText,
-        Box::new(PrintText) as Box<dyn OperationOutput>,
+        OperationType::ImmediateText,
+        Box::new(ImmediateText) as Box<dyn Operation>,
     );
     ops
 }
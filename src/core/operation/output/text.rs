@@ -1,106 +1,94 @@
 use crate::core::error::OperationError;
-use crate::core::operation::OperationOutput;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use crate::utils::file_manager;
 
 pub struct PrintText;
 
-impl OperationOutput for PrintText {
-    fn apply(
-        &self,
-        _stack: &mut Vec<i16>,
-        output: &mut String,
-        text_to_print: &str,
-    ) -> Result<(), OperationError> {
-        if text_to_print.starts_with(".\"") && text_to_print.ends_with('"') {
-            let text = &text_to_print[2..text_to_print.len() - 1];
-            output.push_str(text);
-            return Ok(());
+impl Operation for PrintText {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        match file_manager::parse_print_literal(&context.token) {
+            Some(text) => context.output.write_str(&text),
+            None => Ok(()),
         }
-        Ok(())
     }
 }
 
 #[test]
 fn test_print_text_basic() {
-    let mut stack: Vec<i16> = vec![];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![]);
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" hello world\"".to_string())
-        .unwrap();
+    context.token = ".\" hello world\"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, " hello world");
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), " hello world");
 }
 
 #[test]
 fn test_print_text_multiple_words() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" hello world\"".to_string())
-        .unwrap();
+    context.token = ".\" hello world\"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    assert_eq!(stack, vec![2, 3]);
-    assert_eq!(output, " hello world");
+    assert_eq!(context.stack, vec![2, 3]);
+    assert_eq!(context.output.as_str(), " hello world");
 }
 
 #[test]
 fn test_print_text_empty() {
-    let mut stack: Vec<i16> = vec![];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![]);
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" \"".to_string())
-        .unwrap();
+    context.token = ".\" \"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, " ");
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), " ");
 }
 
 #[test]
 fn test_print_text_mulitple_whitespaces() {
-    let mut stack: Vec<i16> = vec![];
-    let mut output = String::new();
-
-    PrintText
-        .apply(
-            &mut stack,
-            &mut output,
-            &".\" hello      world!\"".to_string(),
-        )
-        .unwrap();
-
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, " hello      world!");
+    let mut context = ExecutionContext::with_stack(vec![]);
+
+    context.token = ".\" hello      world!\"".to_string();
+    PrintText.apply(&mut context).unwrap();
+
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), " hello      world!");
 }
 
 #[test]
 fn test_print_text_dot_qoute_mulitple() {
-    let mut stack: Vec<i16> = vec![];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![]);
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" hello\"".to_string())
-        .unwrap();
+    context.token = ".\" hello\"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" world\"".to_string())
-        .unwrap();
+    context.token = ".\" world\"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    assert_eq!(stack, vec![]);
-    assert_eq!(output, " hello world");
+    assert_eq!(context.stack, vec![]);
+    assert_eq!(context.output.as_str(), " hello world");
 }
 
 #[test]
 fn test_print_text_with_numbers_before() {
-    let mut stack: Vec<i16> = vec![42];
-    let mut output = String::new();
+    let mut context = ExecutionContext::with_stack(vec![42]);
+
+    context.token = ".\" test\"".to_string();
+    PrintText.apply(&mut context).unwrap();
+
+    assert_eq!(context.stack, vec![42]);
+    assert_eq!(context.output.as_str(), " test");
+}
+
+#[test]
+fn test_print_text_escapes() {
+    let mut context = ExecutionContext::with_stack(vec![]);
 
-    PrintText
-        .apply(&mut stack, &mut output, &".\" test\"".to_string())
-        .unwrap();
+    context.token = ".\" say \\\"hi\\\"\\tand\\\\ bye\\n\"".to_string();
+    PrintText.apply(&mut context).unwrap();
 
-    assert_eq!(stack, vec![42]);
-    assert_eq!(output, " test");
+    assert_eq!(context.output.as_str(), " say \"hi\"\tand\\ bye\n");
 }
@@ -1,11 +1,13 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 #[derive(Debug)]
 pub struct Drop;
 
 impl Operation for Drop {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -15,16 +17,16 @@ impl Operation for Drop {
 }
 #[test]
 fn test_drop_last_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Drop.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Drop.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2]);
 }
 
 #[test]
 fn test_underflow_drop() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Drop.apply(&mut stack),
+        Drop.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
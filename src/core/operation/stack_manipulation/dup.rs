@@ -1,11 +1,13 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 #[derive(Debug)]
 pub struct Dup;
 
 impl Operation for Dup {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -18,16 +20,16 @@ impl Operation for Dup {
 }
 #[test]
 fn test_dup_last_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Dup.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 3, 3]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Dup.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3, 3]);
 }
 
 #[test]
 fn test_underflow_dup() {
-    let mut stack: Vec<i16> = vec![];
+    let mut context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Dup.apply(&mut stack),
+        Dup.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,11 +1,13 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 #[derive(Debug)]
 pub struct Over;
 
 impl Operation for Over {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -21,22 +23,22 @@ impl Operation for Over {
 
 #[test]
 fn test_over_last_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Over.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 3, 2]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Over.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3, 2]);
 }
 
 #[test]
 fn test_underflow_over() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Over.apply(&mut stack),
+        Over.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![];
+    let mut second_context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Over.apply(&mut second_stack),
+        Over.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
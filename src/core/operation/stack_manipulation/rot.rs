@@ -1,11 +1,13 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 #[derive(Debug)]
 pub struct Rot;
 
 impl Operation for Rot {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -22,27 +24,27 @@ impl Operation for Rot {
 }
 #[test]
 fn test_rot_elements() {
-    let mut stack: Vec<i16> = vec![1, 2, 3];
-    Rot.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![2, 3, 1]);
+    let mut context = ExecutionContext::with_stack(vec![1, 2, 3]);
+    Rot.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2, 3, 1]);
 }
 
 #[test]
 fn test_underflow_rot() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Rot.apply(&mut stack),
+        Rot.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![];
+    let mut second_context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Rot.apply(&mut second_stack),
+        Rot.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
-    let mut second_stack: Vec<i16> = vec![1, 2];
+    let mut second_context = ExecutionContext::with_stack(vec![1, 2]);
     assert!(matches!(
-        Rot.apply(&mut second_stack),
+        Rot.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
@@ -1,11 +1,13 @@
 use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
 use crate::core::operation::Operation;
 
 #[derive(Debug)]
 pub struct Swap;
 
 impl Operation for Swap {
-    fn apply(&self, stack: &mut Vec<i16>) -> Result<(), OperationError> {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let stack = &mut context.stack;
         if stack.is_empty() {
             return Err(OperationError::StackUnderflow);
         }
@@ -19,22 +21,22 @@ impl Operation for Swap {
 
 #[test]
 fn test_swap_last_number() {
-    let mut stack: Vec<i16> = vec![2, 3];
-    Swap.apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![3, 2]);
+    let mut context = ExecutionContext::with_stack(vec![2, 3]);
+    Swap.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![3, 2]);
 }
 
 #[test]
 fn test_underflow_swap() {
-    let mut stack: Vec<i16> = vec![1];
+    let mut context = ExecutionContext::with_stack(vec![1]);
     assert!(matches!(
-        Swap.apply(&mut stack),
+        Swap.apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 
-    let mut second_stack: Vec<i16> = vec![];
+    let mut second_context = ExecutionContext::with_stack(vec![]);
     assert!(matches!(
-        Swap.apply(&mut second_stack),
+        Swap.apply(&mut second_context),
         Err(OperationError::StackUnderflow)
     ));
 }
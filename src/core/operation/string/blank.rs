@@ -0,0 +1,36 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Stores spaces in a range of addresses: `( addr u -- )`.
+#[derive(Debug)]
+pub struct Blank;
+
+impl Operation for Blank {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let len = context.pop()?;
+        let address = context.pop()?;
+        context.data_space.read(address, len)?;
+        for offset in 0..len {
+            context.data_space.store(address + offset, b' ')?;
+        }
+        Ok(())
+    }
+}
+#[test]
+fn test_blank_range() {
+    let mut context = ExecutionContext::with_stack(vec![1, 2]);
+    context.data_space.store_string("abcd").unwrap();
+    Blank.apply(&mut context).unwrap();
+    assert_eq!(context.data_space.as_bytes(), b"a  d");
+}
+
+#[test]
+fn test_blank_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![3, 2]);
+    context.data_space.store_string("abcd").unwrap();
+    assert!(matches!(
+        Blank.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+}
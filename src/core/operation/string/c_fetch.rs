@@ -0,0 +1,31 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Replaces an address with the byte stored there.
+#[derive(Debug)]
+pub struct CFetch;
+
+impl Operation for CFetch {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let address = context.pop()?;
+        let value = context.data_space.fetch(address)?;
+        context.push(value as i16)
+    }
+}
+#[test]
+fn test_c_fetch_byte() {
+    let mut context = ExecutionContext::with_stack(vec![1]);
+    context.data_space.store_string("AB").unwrap();
+    CFetch.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![66]);
+}
+
+#[test]
+fn test_underflow_c_fetch() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    assert!(matches!(
+        CFetch.apply(&mut context),
+        Err(OperationError::StackUnderflow)
+    ));
+}
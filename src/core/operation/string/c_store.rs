@@ -0,0 +1,32 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Stores the lowest byte of a value at an address: `( c addr -- )`.
+#[derive(Debug)]
+pub struct CStore;
+
+impl Operation for CStore {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let address = context.pop()?;
+        let value = context.pop()?;
+        context.data_space.store(address, value as u8)
+    }
+}
+#[test]
+fn test_c_store_byte() {
+    let mut context = ExecutionContext::with_stack(vec![90, 0]);
+    context.data_space.store_string("AB").unwrap();
+    CStore.apply(&mut context).unwrap();
+    assert_eq!(context.data_space.as_bytes(), b"ZB");
+    assert!(context.stack.is_empty());
+}
+
+#[test]
+fn test_c_store_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![90, 2]);
+    assert!(matches!(
+        CStore.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+}
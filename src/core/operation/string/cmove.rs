@@ -0,0 +1,48 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Copies bytes from one address to another, from the lowest address up: `( src dst u -- )`.
+#[derive(Debug)]
+pub struct Cmove;
+
+impl Operation for Cmove {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let len = context.pop()?;
+        let destination = context.pop()?;
+        let source = context.pop()?;
+        context.data_space.read(source, len)?;
+        context.data_space.read(destination, len)?;
+        for offset in 0..len {
+            let value = context.data_space.fetch(source + offset)?;
+            context.data_space.store(destination + offset, value)?;
+        }
+        Ok(())
+    }
+}
+#[test]
+fn test_cmove_copies_bytes() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3, 2]);
+    context.data_space.store_string("ab---").unwrap();
+    Cmove.apply(&mut context).unwrap();
+    assert_eq!(context.data_space.as_bytes(), b"ab-ab");
+}
+
+#[test]
+fn test_cmove_overlapping_propagates() {
+    let mut context = ExecutionContext::with_stack(vec![0, 1, 3]);
+    context.data_space.store_string("a---").unwrap();
+    Cmove.apply(&mut context).unwrap();
+    assert_eq!(context.data_space.as_bytes(), b"aaaa");
+}
+
+#[test]
+fn test_cmove_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![0, 2, 2]);
+    context.data_space.store_string("abc").unwrap();
+    assert!(matches!(
+        Cmove.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+    assert_eq!(context.data_space.as_bytes(), b"abc");
+}
@@ -0,0 +1,51 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use std::cmp::Ordering;
+
+/// Compares two strings byte by byte: `( a1 u1 a2 u2 -- n )`, where `n` is 0 if they are
+/// equal, -1 if the first one sorts before the second one and 1 otherwise.
+#[derive(Debug)]
+pub struct Compare;
+
+impl Operation for Compare {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let second_len = context.pop()?;
+        let second_address = context.pop()?;
+        let first_len = context.pop()?;
+        let first_address = context.pop()?;
+        let second = context.data_space.read(second_address, second_len)?;
+        let first = context.data_space.read(first_address, first_len)?;
+        let result = match first.cmp(second) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        };
+        context.push(result)
+    }
+}
+#[test]
+fn test_compare_strings() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    context.data_space.store_string("abcabd").unwrap();
+    for (stack, expected) in [
+        (vec![0, 3, 0, 3], 0),
+        (vec![0, 3, 3, 3], -1),
+        (vec![3, 3, 0, 3], 1),
+        (vec![0, 2, 0, 3], -1),
+        (vec![0, 3, 0, 2], 1),
+    ] {
+        context.stack = stack;
+        Compare.apply(&mut context).unwrap();
+        assert_eq!(context.stack, vec![expected]);
+    }
+}
+
+#[test]
+fn test_underflow_compare() {
+    let mut context = ExecutionContext::with_stack(vec![0, 1, 2]);
+    assert!(matches!(
+        Compare.apply(&mut context),
+        Err(OperationError::StackUnderflow)
+    ));
+}
@@ -0,0 +1,44 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Stores the concatenation of two strings as a new string in the data space:
+/// `( a1 u1 a2 u2 -- a3 u3 )`.
+#[derive(Debug)]
+pub struct Concat;
+
+impl Operation for Concat {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let second_len = context.pop()?;
+        let second_address = context.pop()?;
+        let first_len = context.pop()?;
+        let first_address = context.pop()?;
+        let mut bytes = context.data_space.read(first_address, first_len)?.to_vec();
+        bytes.extend_from_slice(context.data_space.read(second_address, second_len)?);
+        let address = context.data_space.allot(&bytes)?;
+        context.push(address)?;
+        context.push(bytes.len() as i16)
+    }
+}
+#[test]
+fn test_concat_strings() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3, 3, 2]);
+    context.data_space.store_string("foo42").unwrap();
+    Concat.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![5, 5]);
+    assert_eq!(
+        context.data_space.read_string(5, 5),
+        Ok("foo42".to_string())
+    );
+}
+
+#[test]
+fn test_concat_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3, 3, 9]);
+    context.data_space.store_string("foo").unwrap();
+    assert!(matches!(
+        Concat.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+    assert_eq!(context.data_space.len(), 3);
+}
@@ -0,0 +1,33 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Turns the address of a counted string, whose first byte is its length, into the
+/// address and length of its text.
+#[derive(Debug)]
+pub struct Count;
+
+impl Operation for Count {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let address = context.pop()?;
+        let len = context.data_space.fetch(address)?;
+        context.push(address + 1)?;
+        context.push(len as i16)
+    }
+}
+#[test]
+fn test_count_counted_string() {
+    let mut context = ExecutionContext::with_stack(vec![0]);
+    context.data_space.store_string("\u{3}abc").unwrap();
+    Count.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![1, 3]);
+}
+
+#[test]
+fn test_count_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![4]);
+    assert!(matches!(
+        Count.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+}
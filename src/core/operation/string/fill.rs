@@ -0,0 +1,38 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Stores the same byte in a range of addresses: `( addr u c -- )`.
+#[derive(Debug)]
+pub struct Fill;
+
+impl Operation for Fill {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let value = context.pop()?;
+        let len = context.pop()?;
+        let address = context.pop()?;
+        context.data_space.read(address, len)?;
+        for offset in 0..len {
+            context.data_space.store(address + offset, value as u8)?;
+        }
+        Ok(())
+    }
+}
+#[test]
+fn test_fill_range() {
+    let mut context = ExecutionContext::with_stack(vec![1, 2, 42]);
+    context.data_space.store_string("abcd").unwrap();
+    Fill.apply(&mut context).unwrap();
+    assert_eq!(context.data_space.as_bytes(), b"a**d");
+}
+
+#[test]
+fn test_fill_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![2, 3, 42]);
+    context.data_space.store_string("abcd").unwrap();
+    assert!(matches!(
+        Fill.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+    assert_eq!(context.data_space.as_bytes(), b"abcd");
+}
@@ -0,0 +1,36 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Shortens a string so it does not end with spaces: `( a u -- a u2 )`.
+#[derive(Debug)]
+pub struct MinusTrailing;
+
+impl Operation for MinusTrailing {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let len = context.pop()?;
+        let address = context.pop()?;
+        let text = context.data_space.read(address, len)?;
+        let trimmed_len = text
+            .iter()
+            .rposition(|byte| *byte != b' ')
+            .map_or(0, |i| i + 1);
+        context.push(address)?;
+        context.push(trimmed_len as i16)
+    }
+}
+#[test]
+fn test_minus_trailing_removes_spaces() {
+    let mut context = ExecutionContext::with_stack(vec![0, 7]);
+    context.data_space.store_string(" ab    ").unwrap();
+    MinusTrailing.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0, 3]);
+}
+
+#[test]
+fn test_minus_trailing_only_spaces() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3]);
+    context.data_space.store_string("   ").unwrap();
+    MinusTrailing.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0, 0]);
+}
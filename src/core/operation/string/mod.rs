@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+pub mod blank;
+pub mod c_fetch;
+pub mod c_store;
+pub mod cmove;
+pub mod compare;
+pub mod concat;
+pub mod count;
+pub mod fill;
+pub mod minus_trailing;
+pub mod search;
+pub mod slash_string;
+pub mod type_string;
+
+pub use blank::Blank;
+pub use c_fetch::CFetch;
+pub use c_store::CStore;
+pub use cmove::Cmove;
+pub use compare::Compare;
+pub use concat::Concat;
+pub use count::Count;
+pub use fill::Fill;
+pub use minus_trailing::MinusTrailing;
+pub use search::Search;
+pub use slash_string::SlashString;
+pub use type_string::TypeString;
+
+use super::{Operation, OperationType};
+
+pub fn get_operations() -> HashMap<OperationType, Box<dyn Operation>> {
+    let mut ops = HashMap::new();
+    ops.insert(
+        OperationType::Type,
+        Box::new(TypeString) as Box<dyn Operation>,
+    );
+    ops.insert(OperationType::Count, Box::new(Count) as Box<dyn Operation>);
+    ops.insert(
+        OperationType::CFetch,
+        Box::new(CFetch) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::CStore,
+        Box::new(CStore) as Box<dyn Operation>,
+    );
+    ops.insert(OperationType::Cmove, Box::new(Cmove) as Box<dyn Operation>);
+    ops.insert(OperationType::Fill, Box::new(Fill) as Box<dyn Operation>);
+    ops.insert(OperationType::Blank, Box::new(Blank) as Box<dyn Operation>);
+    ops.insert(
+        OperationType::Compare,
+        Box::new(Compare) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::Search,
+        Box::new(Search) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::SlashString,
+        Box::new(SlashString) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::MinusTrailing,
+        Box::new(MinusTrailing) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::Concat,
+        Box::new(Concat) as Box<dyn Operation>,
+    );
+    ops
+}
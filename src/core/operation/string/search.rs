@@ -0,0 +1,57 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Looks for the second string inside the first one: `( a1 u1 a2 u2 -- a3 u3 flag )`.
+/// If it is found, `a3 u3` is the rest of the first string starting at the match and
+/// `flag` is true (-1). Otherwise the first string is left unchanged and `flag` is 0.
+#[derive(Debug)]
+pub struct Search;
+
+impl Operation for Search {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let needle_len = context.pop()?;
+        let needle_address = context.pop()?;
+        let len = context.pop()?;
+        let address = context.pop()?;
+        let needle = context.data_space.read(needle_address, needle_len)?;
+        let haystack = context.data_space.read(address, len)?;
+        let found = (0..=haystack.len().saturating_sub(needle.len()))
+            .find(|&offset| haystack[offset..].starts_with(needle));
+        match found {
+            Some(offset) => {
+                context.push(address + offset as i16)?;
+                context.push(len - offset as i16)?;
+                context.push(-1)
+            }
+            None => {
+                context.push(address)?;
+                context.push(len)?;
+                context.push(0)
+            }
+        }
+    }
+}
+#[test]
+fn test_search_found() {
+    let mut context = ExecutionContext::with_stack(vec![0, 11, 11, 2]);
+    context.data_space.store_string("hello worldor").unwrap();
+    Search.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![7, 4, -1]);
+}
+
+#[test]
+fn test_search_not_found() {
+    let mut context = ExecutionContext::with_stack(vec![0, 5, 5, 3]);
+    context.data_space.store_string("hellolol").unwrap();
+    Search.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0, 5, 0]);
+}
+
+#[test]
+fn test_search_empty_string() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3, 0, 0]);
+    context.data_space.store_string("abc").unwrap();
+    Search.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0, 3, -1]);
+}
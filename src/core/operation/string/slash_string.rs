@@ -0,0 +1,32 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Removes the first `n` characters of a string: `( a u n -- a+n u-n )`.
+#[derive(Debug)]
+pub struct SlashString;
+
+impl Operation for SlashString {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let n = context.pop()?;
+        let len = context.pop()?;
+        let address = context.pop()?;
+        context.push(address.wrapping_add(n))?;
+        context.push(len.wrapping_sub(n))
+    }
+}
+#[test]
+fn test_slash_string_drops_characters() {
+    let mut context = ExecutionContext::with_stack(vec![10, 5, 2]);
+    SlashString.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![12, 3]);
+}
+
+#[test]
+fn test_underflow_slash_string() {
+    let mut context = ExecutionContext::with_stack(vec![10, 5]);
+    assert!(matches!(
+        SlashString.apply(&mut context),
+        Err(OperationError::StackUnderflow)
+    ));
+}
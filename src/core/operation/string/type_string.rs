@@ -0,0 +1,34 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Prints the string whose address and length are on the stack.
+#[derive(Debug)]
+pub struct TypeString;
+
+impl Operation for TypeString {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let len = context.pop()?;
+        let address = context.pop()?;
+        let text = context.data_space.read_string(address, len)?;
+        context.output.write_str(&text)
+    }
+}
+#[test]
+fn test_type_prints_string() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    let (address, len) = context.data_space.store_string("hello").unwrap();
+    context.stack = vec![address + 1, len - 1];
+    TypeString.apply(&mut context).unwrap();
+    assert_eq!(context.output.as_str(), "ello");
+    assert!(context.stack.is_empty());
+}
+
+#[test]
+fn test_type_outside_data_space() {
+    let mut context = ExecutionContext::with_stack(vec![0, 3]);
+    assert!(matches!(
+        TypeString.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+}
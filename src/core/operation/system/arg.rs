@@ -0,0 +1,37 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Pushes the address and length of the n-th argument, or `0 0` if there is no such
+/// argument.
+#[derive(Debug)]
+pub struct Arg;
+
+impl Operation for Arg {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let index = context.pop()?;
+        let (address, len) = usize::try_from(index)
+            .ok()
+            .and_then(|index| context.args.get(index).copied())
+            .unwrap_or((0, 0));
+        context.push(address)?;
+        context.push(len)
+    }
+}
+#[test]
+fn test_arg_pushes_address_and_length() {
+    let mut context = ExecutionContext::with_stack(vec![1]);
+    context.args = vec![(0, 10), (10, 3)];
+    Arg.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 3]);
+}
+
+#[test]
+fn test_arg_out_of_range() {
+    for index in [1, -1] {
+        let mut context = ExecutionContext::with_stack(vec![index]);
+        context.args = vec![(0, 10)];
+        Arg.apply(&mut context).unwrap();
+        assert_eq!(context.stack, vec![0, 0]);
+    }
+}
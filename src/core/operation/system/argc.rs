@@ -0,0 +1,20 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Pushes the number of arguments passed to the program, including the name of the script.
+#[derive(Debug)]
+pub struct Argc;
+
+impl Operation for Argc {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        context.push(context.args.len() as i16)
+    }
+}
+#[test]
+fn test_argc_pushes_number_of_arguments() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    context.args = vec![(0, 10), (10, 1)];
+    Argc.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![2]);
+}
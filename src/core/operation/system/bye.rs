@@ -0,0 +1,22 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use crate::core::run_result::Halt;
+
+/// Stops the interpreter without an exit status, leaving the stack as it is.
+#[derive(Debug)]
+pub struct Bye;
+
+impl Operation for Bye {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        context.halted = Some(Halt::Bye);
+        Ok(())
+    }
+}
+#[test]
+fn test_bye_halts() {
+    let mut context = ExecutionContext::with_stack(vec![1, 2]);
+    Bye.apply(&mut context).unwrap();
+    assert_eq!(context.halted, Some(Halt::Bye));
+    assert_eq!(context.stack, vec![1, 2]);
+}
@@ -0,0 +1,33 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use crate::core::run_result::Halt;
+
+/// Pops an exit status and stops the interpreter with it.
+#[derive(Debug)]
+pub struct ExitCode;
+
+impl Operation for ExitCode {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let status = context.pop()?;
+        context.halted = Some(Halt::ExitCode(status));
+        Ok(())
+    }
+}
+#[test]
+fn test_exit_code_halts_with_top_of_stack() {
+    let mut context = ExecutionContext::with_stack(vec![1, 3]);
+    ExitCode.apply(&mut context).unwrap();
+    assert_eq!(context.halted, Some(Halt::ExitCode(3)));
+    assert_eq!(context.stack, vec![1]);
+}
+
+#[test]
+fn test_underflow_exit_code() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    assert!(matches!(
+        ExitCode.apply(&mut context),
+        Err(OperationError::StackUnderflow)
+    ));
+    assert_eq!(context.halted, None);
+}
@@ -0,0 +1,73 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+use std::env;
+
+/// Replaces the name of an environment variable with its value, or with `0 0` if the
+/// variable is not set. The value is stored in a transient buffer of the data space, so
+/// it is only valid until the next `getenv`.
+#[derive(Debug)]
+pub struct Getenv;
+
+impl Operation for Getenv {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let len = context.pop()?;
+        let address = context.pop()?;
+        let name = context.data_space.read_string(address, len)?;
+        let (address, len) = match env::var(name) {
+            Ok(value) => context.data_space.store_transient(&value)?,
+            Err(_) => (0, 0),
+        };
+        context.push(address)?;
+        context.push(len)
+    }
+}
+#[test]
+fn test_getenv_reads_variables() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    let (address, len) = context.data_space.store_string("PATH").unwrap();
+    context.stack = vec![address, len];
+    Getenv.apply(&mut context).unwrap();
+
+    let value = context
+        .data_space
+        .read_string(context.stack[0], context.stack[1])
+        .unwrap();
+    assert_eq!(value, env::var("PATH").unwrap());
+}
+
+#[test]
+fn test_getenv_reuses_its_buffer() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    let (address, len) = context.data_space.store_string("PATH").unwrap();
+    context.stack = vec![address, len];
+    Getenv.apply(&mut context).unwrap();
+    let data_space_len = context.data_space.len();
+
+    for _ in 0..100 {
+        context.stack = vec![address, len];
+        Getenv.apply(&mut context).unwrap();
+    }
+    assert_eq!(context.data_space.len(), data_space_len);
+}
+
+#[test]
+fn test_getenv_missing_variable() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    let (address, len) = context
+        .data_space
+        .store_string("RUST_THE_FORTH_MISSING_VARIABLE")
+        .unwrap();
+    context.stack = vec![address, len];
+    Getenv.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![0, 0]);
+}
+
+#[test]
+fn test_getenv_invalid_name() {
+    let mut context = ExecutionContext::with_stack(vec![0, 4]);
+    assert!(matches!(
+        Getenv.apply(&mut context),
+        Err(OperationError::InvalidAddress)
+    ));
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+pub mod arg;
+pub mod argc;
+pub mod bye;
+pub mod exit_code;
+pub mod getenv;
+pub mod next_arg;
+
+pub use arg::Arg;
+pub use argc::Argc;
+pub use bye::Bye;
+pub use exit_code::ExitCode;
+pub use getenv::Getenv;
+pub use next_arg::NextArg;
+
+use super::{Operation, OperationType};
+
+pub fn get_operations() -> HashMap<OperationType, Box<dyn Operation>> {
+    let mut ops = HashMap::new();
+    ops.insert(OperationType::Bye, Box::new(Bye) as Box<dyn Operation>);
+    ops.insert(
+        OperationType::ExitCode,
+        Box::new(ExitCode) as Box<dyn Operation>,
+    );
+    ops.insert(OperationType::Argc, Box::new(Argc) as Box<dyn Operation>);
+    ops.insert(OperationType::Arg, Box::new(Arg) as Box<dyn Operation>);
+    ops.insert(
+        OperationType::NextArg,
+        Box::new(NextArg) as Box<dyn Operation>,
+    );
+    ops.insert(
+        OperationType::Getenv,
+        Box::new(Getenv) as Box<dyn Operation>,
+    );
+    ops
+}
@@ -0,0 +1,31 @@
+use crate::core::error::OperationError;
+use crate::core::execution_context::ExecutionContext;
+use crate::core::operation::Operation;
+
+/// Pushes the address and length of the next argument that has not been consumed yet,
+/// skipping the name of the script, or `0 0` once every argument was consumed.
+#[derive(Debug)]
+pub struct NextArg;
+
+impl Operation for NextArg {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let (address, len) = match context.args.get(context.next_arg) {
+            Some(arg) => {
+                context.next_arg += 1;
+                *arg
+            }
+            None => (0, 0),
+        };
+        context.push(address)?;
+        context.push(len)
+    }
+}
+#[test]
+fn test_next_arg_consumes_arguments_in_order() {
+    let mut context = ExecutionContext::with_stack(vec![]);
+    context.args = vec![(0, 10), (10, 1), (11, 2)];
+    NextArg.apply(&mut context).unwrap();
+    NextArg.apply(&mut context).unwrap();
+    NextArg.apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 1, 11, 2, 0, 0]);
+}
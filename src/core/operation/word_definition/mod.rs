@@ -4,5 +4,5 @@ pub mod word;
 /// Module declaration for word registration.
 pub mod word_registry;
 
-pub use word::Word;
+pub use word::{Definition, Word};
 pub use word_registry::WordRegistry;
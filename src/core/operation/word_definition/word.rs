@@ -1,11 +1,47 @@
+use crate::core::operation::Operation;
+use std::fmt;
+use std::rc::Rc;
+
+/// What a word runs when it is called.
+pub enum Definition {
+    /// A sequence of operations or instructions, as written in a colon definition.
+    Body(Vec<String>),
+    /// The Rust implementation of a word registered as a native word.
+    Native(Rc<dyn Operation>),
+    /// A native word restored from an image whose Rust implementation has not been
+    /// registered again yet.
+    UnboundNative,
+}
+
+impl fmt::Debug for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Definition::Body(body) => f.debug_tuple("Body").field(body).finish(),
+            Definition::Native(_) => f.write_str("Native"),
+            Definition::UnboundNative => f.write_str("UnboundNative"),
+        }
+    }
+}
+
 /// A structure representing a word in the registry.
 ///
-/// A word consists of a name and a body. The `body` is typically a sequence of operations or
-/// instructions associated with the word. This structure allows us to store and manipulate word definitions.
+/// A word consists of a name and a definition. The definition is typically a body, a sequence of
+/// operations or instructions associated with the word. This structure allows us to store and manipulate word definitions.
+/// Words registered from Rust carry a native implementation instead of a body.
 #[derive(Debug)]
 pub struct Word {
     /// The name of the word (e.g., `dup`, `+`, `swap`, etc.).
     pub name: String,
-    /// The body of the word, which is a sequence of operations or instructions that define its behavior.
-    pub body: Vec<String>,
+    /// What the word runs when it is called.
+    pub definition: Definition,
+}
+
+impl Word {
+    /// Returns the body of the word, or `None` if it is a native word.
+    pub fn body(&self) -> Option<&[String]> {
+        match &self.definition {
+            Definition::Body(body) => Some(body),
+            Definition::Native(_) | Definition::UnboundNative => None,
+        }
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use super::Word;
+use super::{Definition, Word};
+use crate::core::operation::Operation;
 
 /// A registry for storing word definitions and their versions.
 ///
@@ -37,10 +39,54 @@ impl WordRegistry {
     /// mapping for that name, leaving older definitions intact. This way, any words that referenced
     /// a previous definition retain their behavior.
     pub fn define_word(&mut self, name: String, body: Vec<String>) {
-        self.words.push(Word {
-            name: name.to_string(),
-            body,
+        self.push_word(Word {
+            name,
+            definition: Definition::Body(body),
         });
+    }
+
+    /// Defines a new version of a word implemented in Rust.
+    ///
+    /// Native words follow the same versioning as user definitions: they shadow any
+    /// previous definition with the same name from now on, while words that referenced
+    /// the previous definition keep their behavior.
+    pub fn define_native(&mut self, name: String, operation: Rc<dyn Operation>) {
+        self.push_word(Word {
+            name,
+            definition: Definition::Native(operation),
+        });
+    }
+
+    /// Defines a new version of a native word whose implementation is not available yet,
+    /// e.g. when restoring an image.
+    ///
+    /// Calling the word fails until `bind_native` provides its implementation.
+    pub fn define_unbound_native(&mut self, name: String) {
+        self.push_word(Word {
+            name,
+            definition: Definition::UnboundNative,
+        });
+    }
+
+    /// Provides the implementation of every unbound native version of a word.
+    ///
+    /// # Returns
+    /// `true` if at least one version of the word was bound.
+    pub fn bind_native(&mut self, name: &str, operation: Rc<dyn Operation>) -> bool {
+        let mut bound = false;
+        for word in self.words.iter_mut().filter(|word| {
+            matches!(word.definition, Definition::UnboundNative) && word.name == name
+        }) {
+            word.definition = Definition::Native(Rc::clone(&operation));
+            bound = true;
+        }
+        bound
+    }
+
+    /// Appends a word to `words` and makes it the current definition of its name.
+    fn push_word(&mut self, word: Word) {
+        let name = word.name.clone();
+        self.words.push(word);
         let new_index = self.words.len() - 1;
 
         let exists = self.current_definition.contains_key(&name);
@@ -99,6 +145,19 @@ impl WordRegistry {
             false
         }
     }
+
+    /// Removes every definition added after the registry had `len` words, restoring
+    /// the names they shadowed.
+    ///
+    /// # Parameters
+    /// - `len`: The number of definitions to keep.
+    pub fn truncate(&mut self, len: usize) {
+        self.words.truncate(len);
+        self.current_definition.retain(|_, versions| {
+            versions.retain(|index| *index < len);
+            !versions.is_empty()
+        });
+    }
 }
 
 #[cfg(test)]
@@ -119,7 +178,7 @@ mod tests {
 
         assert_eq!(registry.words.len(), 1);
         assert_eq!(registry.words[0].name, "sum");
-        assert_eq!(registry.words[0].body, vec!["+"]);
+        assert_eq!(registry.words[0].body().unwrap(), ["+"]);
 
         let versions = registry.get_word_versions("sum").unwrap();
         assert_eq!(versions.len(), 1);
@@ -140,9 +199,9 @@ mod tests {
         assert_eq!(versions.len(), 3);
         assert_eq!(versions, &vec![0, 1, 2]);
 
-        assert_eq!(registry.words[versions[0]].body, vec!["."]);
-        assert_eq!(registry.words[versions[1]].body, vec![".s"]);
-        assert_eq!(registry.words[versions[2]].body, vec![".x"]);
+        assert_eq!(registry.words[versions[0]].body().unwrap(), ["."]);
+        assert_eq!(registry.words[versions[1]].body().unwrap(), [".s"]);
+        assert_eq!(registry.words[versions[2]].body().unwrap(), [".x"]);
     }
 
     #[test]
@@ -185,4 +244,52 @@ mod tests {
         assert!(!registry.has_version("w", 2));
         assert!(!registry.has_version("nonexistent", 0));
     }
+
+    #[test]
+    fn test_define_native_adds_a_new_version() {
+        let mut registry = WordRegistry::new();
+        registry.define_word("w".to_string(), vec!["1".to_string()]);
+        registry.define_native(
+            "w".to_string(),
+            Rc::new(|_: &mut crate::core::execution_context::ExecutionContext| Ok(())),
+        );
+
+        assert_eq!(registry.get_word_versions("w"), Some(&vec![0, 1]));
+        assert!(matches!(registry.words[0].definition, Definition::Body(_)));
+        assert!(matches!(
+            registry.words[1].definition,
+            Definition::Native(_)
+        ));
+    }
+
+    #[test]
+    fn test_bind_native_binds_only_unbound_versions() {
+        let mut registry = WordRegistry::new();
+        registry.define_word("w".to_string(), vec!["1".to_string()]);
+        registry.define_unbound_native("w".to_string());
+        let operation: Rc<dyn Operation> =
+            Rc::new(|_: &mut crate::core::execution_context::ExecutionContext| Ok(()));
+
+        assert!(registry.bind_native("w", Rc::clone(&operation)));
+        assert!(matches!(registry.words[0].definition, Definition::Body(_)));
+        assert!(matches!(
+            registry.words[1].definition,
+            Definition::Native(_)
+        ));
+        assert!(!registry.bind_native("w", operation));
+    }
+
+    #[test]
+    fn test_truncate_restores_previous_definitions() {
+        let mut registry = WordRegistry::new();
+        registry.define_word("w".to_string(), vec!["x".to_string()]);
+        registry.define_word("w".to_string(), vec!["y".to_string()]);
+        registry.define_word("z".to_string(), vec!["1".to_string()]);
+
+        registry.truncate(1);
+
+        assert_eq!(registry.words.len(), 1);
+        assert_eq!(registry.get_word_versions("w"), Some(&vec![0]));
+        assert!(!registry.contains_key("z"));
+    }
 }
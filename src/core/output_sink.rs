@@ -0,0 +1,107 @@
+use crate::core::error::OperationError;
+use std::fmt;
+use std::io::Write;
+
+/// Destination of the text produced while running Forth code.
+///
+/// By default the text is kept in memory, but it can also be streamed to any
+/// `io::Write` (e.g. stdout) or handed to a callback as soon as it is produced.
+pub enum OutputSink {
+    /// Keeps the text in memory.
+    Buffer(String),
+    /// Writes the text to the wrapped writer, flushing after every write.
+    Writer(Box<dyn Write>),
+    /// Calls the wrapped function with every piece of text.
+    Callback(Box<dyn FnMut(&str)>),
+}
+
+impl Default for OutputSink {
+    fn default() -> Self {
+        OutputSink::Buffer(String::new())
+    }
+}
+
+impl fmt::Debug for OutputSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputSink::Buffer(buffer) => f.debug_tuple("Buffer").field(buffer).finish(),
+            OutputSink::Writer(_) => f.write_str("Writer"),
+            OutputSink::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+impl OutputSink {
+    /// Creates a sink that streams the text to `writer`.
+    pub fn writer<W: Write + 'static>(writer: W) -> Self {
+        OutputSink::Writer(Box::new(writer))
+    }
+
+    /// Creates a sink that calls `callback` with every piece of text.
+    pub fn callback<F: FnMut(&str) + 'static>(callback: F) -> Self {
+        OutputSink::Callback(Box::new(callback))
+    }
+
+    /// Sends `text` to the sink.
+    ///
+    /// # Returns
+    /// * `Err(OperationError::FailWritingOutput)` if the underlying writer fails.
+    pub fn write_str(&mut self, text: &str) -> Result<(), OperationError> {
+        match self {
+            OutputSink::Buffer(buffer) => buffer.push_str(text),
+            OutputSink::Writer(writer) => writer
+                .write_all(text.as_bytes())
+                .and_then(|_| writer.flush())
+                .map_err(|_| OperationError::FailWritingOutput)?,
+            OutputSink::Callback(callback) => callback(text),
+        }
+        Ok(())
+    }
+
+    /// Returns the text kept in memory, which is empty unless the sink is a buffer.
+    pub fn as_str(&self) -> &str {
+        match self {
+            OutputSink::Buffer(buffer) => buffer,
+            _ => "",
+        }
+    }
+
+    /// Discards the text kept in memory, if any.
+    pub fn clear(&mut self) {
+        if let OutputSink::Buffer(buffer) = self {
+            buffer.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_buffer_keeps_text() {
+        let mut sink = OutputSink::default();
+        sink.write_str("1 ").unwrap();
+        sink.write_str("2 ").unwrap();
+        assert_eq!(sink.as_str(), "1 2 ");
+
+        sink.clear();
+        assert_eq!(sink.as_str(), "");
+    }
+
+    #[test]
+    fn test_callback_receives_every_write() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let received_in_callback = Rc::clone(&received);
+        let mut sink = OutputSink::callback(move |text| {
+            received_in_callback.borrow_mut().push(text.to_string())
+        });
+
+        sink.write_str("a").unwrap();
+        sink.write_str("\n").unwrap();
+        assert_eq!(*received.borrow(), vec!["a", "\n"]);
+        assert_eq!(sink.as_str(), "");
+    }
+}
@@ -0,0 +1,56 @@
+use crate::core::error::ForthError;
+use std::time::Duration;
+
+/// How `bye` or `exit-code` stopped an evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halt {
+    /// `bye`: the program ends without choosing an exit status.
+    Bye,
+    /// `exit-code`: the program ends with the given exit status.
+    ExitCode(i16),
+}
+
+/// Counters collected while evaluating code.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionStats {
+    /// Number of tokens executed (numbers, operations and words), including the ones
+    /// inside user-defined words.
+    pub tokens_executed: usize,
+    /// Number of calls to user-defined or native words.
+    pub words_called: usize,
+    /// Largest number of elements the stack held.
+    pub max_stack_depth: usize,
+    /// Time spent evaluating the code.
+    pub elapsed: Duration,
+}
+
+/// Everything produced by a call to `ForthCalculator::evaluate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    /// The stack once the evaluation finished.
+    pub stack: Vec<i16>,
+    /// The output kept in memory during the evaluation. It is empty when the output
+    /// is streamed to a writer or a callback.
+    pub output: String,
+    /// The errors reported during the evaluation, with their positions.
+    pub errors: Vec<ForthError>,
+    /// Names of the words defined during the evaluation, in definition order.
+    pub defined_words: Vec<String>,
+    /// Execution statistics.
+    pub stats: ExecutionStats,
+    /// Whether `bye` or `exit-code` halted the evaluation before running every token.
+    /// A halted run is not a failed one.
+    pub halted: Option<Halt>,
+}
+
+impl RunResult {
+    /// Returns `true` if the evaluation did not report any error.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `true` if `bye` or `exit-code` stopped the evaluation.
+    pub fn is_halted(&self) -> bool {
+        self.halted.is_some()
+    }
+}
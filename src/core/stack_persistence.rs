@@ -0,0 +1,55 @@
+use crate::utils::file_manager;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Selects where `ForthCalculator` saves the stack after every evaluation.
+#[derive(Default)]
+pub enum StackPersistence {
+    /// The stack is not saved.
+    #[default]
+    None,
+    /// The stack is written to the file at the given path, replacing its content.
+    File(PathBuf),
+    /// The stack is written to the given writer, one line per evaluation.
+    Writer(Box<dyn Write>),
+}
+
+impl fmt::Debug for StackPersistence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StackPersistence::None => f.write_str("None"),
+            StackPersistence::File(path) => f.debug_tuple("File").field(path).finish(),
+            StackPersistence::Writer(_) => f.write_str("Writer"),
+        }
+    }
+}
+
+impl StackPersistence {
+    /// Creates a policy that saves the stack to the file at `path`.
+    pub fn file<P: Into<PathBuf>>(path: P) -> Self {
+        StackPersistence::File(path.into())
+    }
+
+    /// Creates a policy that writes the stack to `writer`.
+    pub fn writer<W: Write + 'static>(writer: W) -> Self {
+        StackPersistence::Writer(Box::new(writer))
+    }
+
+    /// Saves the stack according to the policy, as space-separated numbers. Every stack
+    /// written to a writer ends with a newline, so the stacks of consecutive evaluations
+    /// stay apart.
+    ///
+    /// # Arguments
+    /// * `stack` - A slice of `i16` integers representing the stack.
+    pub fn save(&mut self, stack: &[i16]) -> io::Result<()> {
+        match self {
+            StackPersistence::None => Ok(()),
+            StackPersistence::File(path) => file_manager::save_stack_to(path, stack),
+            StackPersistence::Writer(writer) => {
+                writeln!(writer, "{}", file_manager::format_stack(stack))?;
+                writer.flush()
+            }
+        }
+    }
+}
@@ -1,43 +1,176 @@
-use rust_the_forth::core::error::OperationError;
+use rust_the_forth::core::error::{ForthError, OperationError, RecoveryPolicy};
 use rust_the_forth::core::forth_calculator::ForthCalculator;
-use rust_the_forth::utils::{cli_manager, file_manager};
+use rust_the_forth::core::output_sink::OutputSink;
+use rust_the_forth::core::stack_persistence::StackPersistence;
+use rust_the_forth::utils::cli_manager::{self, CliOptions};
+use rust_the_forth::utils::repl::Repl;
+use std::cell::RefCell;
 use std::env;
 use std::io::{self, Write};
+use std::process::ExitCode;
+use std::rc::Rc;
 
-fn main() -> std::io::Result<()> {
-    run_app(std::io::stdout())
+fn main() -> ExitCode {
+    match run_app(io::stdout()) {
+        Ok(status) => ExitCode::from(status),
+        Err(_) => ExitCode::from(cli_manager::EXIT_IO_ERROR),
+    }
 }
 
-pub fn run_app<W: Write>(mut out: W) -> io::Result<()> {
+/// Runs the program and returns its exit status.
+pub fn run_app<W: Write + 'static>(out: W) -> io::Result<u8> {
     let args: Vec<String> = env::args().collect();
+    let mut out = SharedWriter(Rc::new(RefCell::new(out)));
 
-    if let Err(e) = cli_manager::validate_command_args(&args) {
-        write!(out, "Error: {}", e)?;
-        return Ok(());
+    let options = match cli_manager::parse_command_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            write!(io::stderr(), "Error: {}", e)?;
+            return Ok(cli_manager::EXIT_USAGE_ERROR);
+        }
+    };
+    if options.help {
+        write!(out, "{}", cli_manager::USAGE)?;
+        return Ok(cli_manager::EXIT_SUCCESS);
+    }
+    if options.version {
+        writeln!(
+            out,
+            "{} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        )?;
+        return Ok(cli_manager::EXIT_SUCCESS);
     }
 
-    let content;
-    let error = match file_manager::read_to_string(&args[1]) {
-        Ok(content_str) => {
-            content = content_str;
-            false
-        }
-        Err(_) => {
-            content = String::new();
-            true
+    let mut forth_calculator = match create_calculator(&options) {
+        Ok(forth_calculator) => forth_calculator,
+        Err(e) => {
+            write!(io::stderr(), "{}", e)?;
+            return Ok(cli_manager::exit_status_for(&e));
         }
     };
+    forth_calculator.set_output(OutputSink::writer(out.clone()));
+    forth_calculator.set_error_output(OutputSink::writer(io::stderr()));
 
-    if error {
-        if file_manager::save_stack(&Vec::new()).is_err() {
-            write!(out, "{}", OperationError::FailWritingFile)?;
+    let mut status = cli_manager::EXIT_SUCCESS;
+    if let Some(prelude) = &options.prelude {
+        let result = forth_calculator.include(prelude);
+        status = error_status(&result.errors);
+        if let Some(halt) = result.halted {
+            status = cli_manager::exit_status_for_halt(halt, status);
+            return Ok(status.max(save_image(&options, &forth_calculator)?));
         }
-        write!(out, "{}", OperationError::FailWritingFile)?;
-        return Ok(());
+    }
+    let halt_on_error = forth_calculator.get_recovery_policy() == RecoveryPolicy::Halt;
+    let skip_sources = status != cli_manager::EXIT_SUCCESS && halt_on_error;
+    for source in options.sources.iter().filter(|_| !skip_sources) {
+        let content = match source.read() {
+            Ok(content) => content,
+            Err(_) => {
+                // The stack file keeps what the previous sources left.
+                write!(
+                    io::stderr(),
+                    "{}: {}",
+                    source.name(),
+                    OperationError::FailReadFile
+                )?;
+                return Ok(cli_manager::EXIT_IO_ERROR);
+            }
+        };
+        forth_calculator.set_source_name(source.name());
+        let result = forth_calculator.evaluate(&content);
+        status = status.max(error_status(&result.errors));
+        if let Some(halt) = result.halted {
+            status = cli_manager::exit_status_for_halt(halt, status);
+            return Ok(status.max(save_image(&options, &forth_calculator)?));
+        }
+        if !result.is_ok() && halt_on_error {
+            break;
+        }
+    }
+
+    if options.is_repl_mode() {
+        let mut repl = Repl::new(forth_calculator);
+        repl.run(io::stdin().lock(), out)?;
+        status = status.max(error_status(repl.errors()));
+        if let Some(halt) = repl.halted() {
+            status = cli_manager::exit_status_for_halt(halt, status);
+        }
+        return Ok(status.max(save_image(&options, repl.calculator())?));
+    }
+    Ok(status.max(save_image(&options, &forth_calculator)?))
+}
+
+/// Returns the worst exit status among the reported errors, or `EXIT_SUCCESS` if there
+/// are none.
+fn error_status(errors: &[ForthError]) -> u8 {
+    errors
+        .iter()
+        .map(|error| cli_manager::exit_status_for(&error.error))
+        .max()
+        .unwrap_or(cli_manager::EXIT_SUCCESS)
+}
+
+/// Lets the interpreter and the REPL write to the same output.
+struct SharedWriter<W>(Rc<RefCell<W>>);
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        SharedWriter(Rc::clone(&self.0))
+    }
+}
+
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
     }
 
-    let mut forth_calculator = ForthCalculator::new(cli_manager::get_size_of_stack(&args));
-    forth_calculator.run(content);
-    write!(out, "{}", forth_calculator.get_output())?;
-    Ok(())
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// Creates the interpreter configured by the command-line options: its stack size, the
+/// image or saved stack it starts from, how errors are handled and where the stack is saved.
+fn create_calculator(options: &CliOptions) -> Result<ForthCalculator, OperationError> {
+    let mut forth_calculator = match (&options.load_image, &options.load_stack) {
+        (Some(path), _) => ForthCalculator::load_image(path)?,
+        (None, Some(path)) => ForthCalculator::from_stack_file(options.stack_size, path)?,
+        (None, None) => ForthCalculator::new(options.stack_size),
+    };
+    // An image keeps its settings unless they are passed explicitly.
+    if options.load_image.is_none() {
+        forth_calculator.set_recovery_policy(cli_manager::DEFAULT_RECOVERY_POLICY);
+    }
+    if let Some(recovery_policy) = options.recovery_policy {
+        forth_calculator.set_recovery_policy(recovery_policy);
+    }
+    if let Some(error_format) = options.error_format {
+        forth_calculator.set_error_format(error_format);
+    }
+    if options.transactional {
+        forth_calculator.set_transactional(true);
+    }
+    forth_calculator.set_args(&options.program_args())?;
+    forth_calculator
+        .set_search_path(&options.search_path(env::var_os(cli_manager::FORTH_PATH_VAR).as_deref()));
+    if options.trace {
+        forth_calculator.set_trace_output(OutputSink::writer(io::stderr()));
+    }
+    if let Some(stack_file) = &options.stack_file {
+        forth_calculator.set_stack_persistence(StackPersistence::file(stack_file));
+    }
+    Ok(forth_calculator)
+}
+
+/// Saves the interpreter image if `--save-image` was passed, returning the exit status.
+fn save_image(options: &CliOptions, forth_calculator: &ForthCalculator) -> io::Result<u8> {
+    if let Some(path) = &options.save_image {
+        if let Err(e) = forth_calculator.save_image(path) {
+            write!(io::stderr(), "{}", e)?;
+            return Ok(cli_manager::exit_status_for(&e));
+        }
+    }
+    Ok(cli_manager::EXIT_SUCCESS)
 }
@@ -1,8 +1,281 @@
-use crate::core::error::CommandArgsError;
+use crate::core::error::{CommandArgsError, ErrorFormat, OperationError, RecoveryPolicy};
+use crate::core::run_result::Halt;
+use crate::utils::file_manager;
+use std::env;
+use std::ffi::OsStr;
+use std::io;
+use std::path::PathBuf;
+
+/// Exit status when every source ran without errors
+pub const EXIT_SUCCESS: u8 = 0;
+
+/// Exit status when a source reported a runtime error (e.g. `stack-underflow`)
+pub const EXIT_RUNTIME_ERROR: u8 = 1;
+
+/// Exit status when the command-line arguments are invalid
+pub const EXIT_USAGE_ERROR: u8 = 2;
+
+/// Exit status when a source, stack or image file cannot be read or written
+pub const EXIT_IO_ERROR: u8 = 3;
+
+/// Recovery policy when neither `--on-error` nor an image sets one
+pub const DEFAULT_RECOVERY_POLICY: RecoveryPolicy = RecoveryPolicy::Halt;
 
 /// Size in bytes of an i16 type (2 bytes)
 const I16_SIZE: i16 = 2;
 
+/// Default stack size in bytes
+const DEFAULT_STACK_SIZE: i16 = 128;
+
+/// Argument that reads a source from the standard input
+const STDIN_ARG: &str = "-";
+
+/// Argument after which every argument is passed to the program
+const PROGRAM_ARGS_SEPARATOR: &str = "--";
+
+/// Flag followed by a piece of code to run
+const INLINE_CODE_FLAG: &str = "-e";
+
+/// Flag followed by a directory to add to the library search path
+const INCLUDE_DIR_FLAG: &str = "-I";
+
+/// Environment variable with more directories of the library search path
+pub const FORTH_PATH_VAR: &str = "FORTH_PATH";
+
+/// Short form of `--help`
+const SHORT_HELP_FLAG: &str = "-h";
+
+/// Flag that sets the stack size in bytes
+const STACK_SIZE_FLAG: &str = "stack-size";
+
+/// Flag that sets the error recovery policy
+const ON_ERROR_FLAG: &str = "on-error";
+
+/// Flag that sets how errors are written
+const FORMAT_FLAG: &str = "format";
+
+/// Flag that sets the file where the stack is saved
+const OUTPUT_FLAG: &str = "output";
+
+/// Flag that sets the file the initial stack is read from
+const LOAD_STACK_FLAG: &str = "load-stack";
+
+/// Flag that sets the interpreter image to start from
+const LOAD_IMAGE_FLAG: &str = "load-image";
+
+/// Flag that sets the file where the interpreter image is saved
+const SAVE_IMAGE_FLAG: &str = "save-image";
+
+/// Flag that sets a file to run before the sources
+const PRELUDE_FLAG: &str = "prelude";
+
+/// Flag that writes an execution trace to stderr
+const TRACE_FLAG: &str = "trace";
+
+/// Flag that rolls back the stack, the words and the data space of a source or REPL line
+/// that fails
+const TRANSACTIONAL_FLAG: &str = "transactional";
+
+/// Flag that disables saving the stack
+const NO_SAVE_FLAG: &str = "no-save";
+
+/// Flag that starts the interactive REPL once the sources (if any) have run
+const REPL_FLAG: &str = "repl";
+
+/// Flag that prints the usage
+const HELP_FLAG: &str = "help";
+
+/// Flag that prints the version
+const VERSION_FLAG: &str = "version";
+
+/// Flags followed by a value, either as `--flag value` or `--flag=value`
+const VALUE_FLAGS: [&str; 8] = [
+    STACK_SIZE_FLAG,
+    ON_ERROR_FLAG,
+    FORMAT_FLAG,
+    OUTPUT_FLAG,
+    LOAD_STACK_FLAG,
+    LOAD_IMAGE_FLAG,
+    SAVE_IMAGE_FLAG,
+    PRELUDE_FLAG,
+];
+
+/// Flags that do not take a value
+const SWITCH_FLAGS: [&str; 6] = [
+    TRACE_FLAG,
+    TRANSACTIONAL_FLAG,
+    NO_SAVE_FLAG,
+    REPL_FLAG,
+    HELP_FLAG,
+    VERSION_FLAG,
+];
+
+/// Text printed by `--help`
+pub const USAGE: &str = "\
+Usage: rust-the-forth [OPTIONS] [SOURCES...] [-- ARGS...]
+
+Runs every source in order in the same interpreter. Without sources, starts the REPL.
+If the first source is a file that starts with a '#!' line, it runs as a script and
+every argument after it is passed to the script. The words argc, arg, argv and
+next-arg give access to these arguments, or to the ones after '--'.
+
+Sources:
+  <file>                 A file to run (its name must contain a '.')
+  -                      Read the code from stdin
+  -e <code>              Run the given code
+  -- <args...>           Pass the remaining arguments to the program
+
+Options:
+  --stack-size <bytes>   Size of the stack in bytes [default: 128]
+  --on-error <policy>    What to do after an error: halt, line or continue [default: halt]
+  --format <format>      How errors are written: short or detailed [default: short]
+  --transactional        Undo the changes of a source or REPL line that fails
+  --output <path>        File where the final stack is saved [default: stack.fth]
+  --no-save              Do not save the final stack
+  --load-stack <path>    Start from a stack saved by a previous run
+  --load-image <path>    Start from an interpreter image, with its stack size. Its
+                         --on-error, --format and --transactional apply unless passed
+  --save-image <path>    Save an interpreter image after running
+  --prelude <path>       Run the given file before the sources
+  -I <dir>               Look for included files in the given directory (repeatable)
+  --trace                Write an execution trace to stderr
+  --repl                 Start the REPL after running the sources
+  -h, --help             Print this help
+  --version              Print the version
+
+Environment:
+  FORTH_PATH             Directories where included files are looked for after the
+                         ones given with -I, separated like PATH
+
+Exit status:
+  0  Every source ran without errors
+  1  A source reported a runtime error
+  2  The arguments are invalid
+  3  A file could not be read or written
+  n  The status passed to `exit-code` (`bye` exits with 0)
+";
+
+/// A piece of Forth code passed to the program.
+///
+/// Every source is evaluated, in the order it was passed, by the same interpreter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgramSource {
+    /// A file to read (any argument containing a `.`).
+    File(String),
+    /// The standard input (`-`).
+    Stdin,
+    /// Code passed on the command line (`-e <code>`).
+    Inline(String),
+}
+
+impl ProgramSource {
+    /// Returns the name used for the source in error messages.
+    pub fn name(&self) -> &str {
+        match self {
+            ProgramSource::File(path) => path,
+            ProgramSource::Stdin => "<stdin>",
+            ProgramSource::Inline(_) => "<inline>",
+        }
+    }
+
+    /// Reads the whole code of the source.
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            ProgramSource::File(path) => file_manager::read_to_string(path),
+            ProgramSource::Stdin => file_manager::read_stdin(),
+            ProgramSource::Inline(code) => Ok(code.clone()),
+        }
+    }
+}
+
+/// The configuration selected with the command-line arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliOptions {
+    /// Sources of code to run, in the order they were passed.
+    pub sources: Vec<ProgramSource>,
+    /// Arguments passed to the program, i.e. everything after `--` or after a first source
+    /// that starts with a shebang line.
+    pub script_args: Vec<String>,
+    /// Maximum number of elements in the stack.
+    pub stack_size: i16,
+    /// What to do with the remaining tokens after an error, if `--on-error` was passed.
+    pub recovery_policy: Option<RecoveryPolicy>,
+    /// How errors are written, if `--format` was passed.
+    pub error_format: Option<ErrorFormat>,
+    /// File where the final stack is saved, or `None` if it is not saved.
+    pub stack_file: Option<String>,
+    /// Saved stack to start from.
+    pub load_stack: Option<String>,
+    /// Interpreter image to start from.
+    pub load_image: Option<String>,
+    /// File where the interpreter image is saved after running.
+    pub save_image: Option<String>,
+    /// File run before the sources, looked up like an included file.
+    pub prelude: Option<String>,
+    /// Directories added to the library search path with `-I`, in order.
+    pub include_dirs: Vec<String>,
+    /// Whether a source or REPL line that fails is rolled back.
+    pub transactional: bool,
+    /// Whether an execution trace is written to stderr.
+    pub trace: bool,
+    /// Whether the REPL was requested with `--repl`.
+    pub repl: bool,
+    /// Whether the usage was requested.
+    pub help: bool,
+    /// Whether the version was requested.
+    pub version: bool,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        CliOptions {
+            sources: Vec::new(),
+            script_args: Vec::new(),
+            stack_size: convert_bytes_to_elements_amount(DEFAULT_STACK_SIZE),
+            recovery_policy: None,
+            error_format: None,
+            stack_file: Some(file_manager::DEFAULT_STACK_FILE.to_string()),
+            load_stack: None,
+            load_image: None,
+            save_image: None,
+            prelude: None,
+            include_dirs: Vec::new(),
+            transactional: false,
+            trace: false,
+            repl: false,
+            help: false,
+            version: false,
+        }
+    }
+}
+
+impl CliOptions {
+    /// Checks if the REPL has to start, either because `--repl` was passed or because
+    /// there is no source to run.
+    pub fn is_repl_mode(&self) -> bool {
+        self.repl || self.sources.is_empty()
+    }
+
+    /// Returns the library search path: the directories passed with `-I` followed by the
+    /// ones in `forth_path` (usually the value of `FORTH_PATH`).
+    pub fn search_path(&self, forth_path: Option<&OsStr>) -> Vec<PathBuf> {
+        self.include_dirs
+            .iter()
+            .map(PathBuf::from)
+            .chain(forth_path.into_iter().flat_map(env::split_paths))
+            .collect()
+    }
+
+    /// Returns the arguments given to the running code: the name of the first source
+    /// (empty if there is none) followed by the script arguments.
+    pub fn program_args(&self) -> Vec<String> {
+        let name = self.sources.first().map_or("", ProgramSource::name);
+        std::iter::once(name.to_string())
+            .chain(self.script_args.iter().cloned())
+            .collect()
+    }
+}
+
 /// Converts a byte size to the number of i16 elements
 ///
 /// # Arguments
@@ -17,35 +290,205 @@ fn convert_bytes_to_elements_amount(size: i16) -> i16 {
     size / I16_SIZE
 }
 
-/// Validates the stack size argument (format: "stack-size=N")
+/// Parses the value of `--stack-size` (a size in bytes) into a number of elements
+///
+/// # Arguments
+/// * `value` - String containing the size in bytes
+///
+/// # Example
+/// ```text
+/// let elements = parse_stack_size("10"); // Ok(5)
+/// ```
+fn parse_stack_size(value: &str) -> Result<i16, CommandArgsError> {
+    match value.parse::<i16>() {
+        Ok(size) if convert_bytes_to_elements_amount(size) <= 0 => {
+            Err(CommandArgsError::InvalidStackSize)
+        }
+        Ok(size) => Ok(convert_bytes_to_elements_amount(size)),
+        Err(_) => Err(CommandArgsError::FailParseStackSize),
+    }
+}
+
+/// Parses the value of `--on-error`
+///
+/// # Arguments
+/// * `value` - One of `halt`, `line` or `continue`
+///
+/// # Example
+/// ```text
+/// let policy = parse_recovery_policy("line"); // Ok(RecoveryPolicy::SkipLine)
+/// ```
+fn parse_recovery_policy(value: &str) -> Result<RecoveryPolicy, CommandArgsError> {
+    match value {
+        "halt" => Ok(RecoveryPolicy::Halt),
+        "line" => Ok(RecoveryPolicy::SkipLine),
+        "continue" => Ok(RecoveryPolicy::Continue),
+        _ => Err(CommandArgsError::InvalidRecoveryPolicy),
+    }
+}
+
+/// Parses the value of `--format`
+///
+/// # Arguments
+/// * `value` - Either `short` or `detailed`
+fn parse_error_format(value: &str) -> Result<ErrorFormat, CommandArgsError> {
+    match value {
+        "short" => Ok(ErrorFormat::Short),
+        "detailed" => Ok(ErrorFormat::Detailed),
+        _ => Err(CommandArgsError::InvalidErrorFormat),
+    }
+}
+
+/// Splits an argument into the name of the flag and its inline value, if it is a flag
+///
+/// Flags are written as `--name`, `--name=value` or, for compatibility with older
+/// versions, `name=value`.
+///
+/// # Returns
+/// * `Ok(None)` if the argument is not a flag.
+/// * `Err(CommandArgsError::UnknownOption)` if it starts with `-` but is not a known flag.
+fn split_flag(arg: &str) -> Result<Option<(&str, Option<&str>)>, CommandArgsError> {
+    let (name, value) = if let Some(flag) = arg.strip_prefix("--") {
+        match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (flag, None),
+        }
+    } else if arg == SHORT_HELP_FLAG {
+        (HELP_FLAG, None)
+    } else if arg.starts_with('-') {
+        return Err(CommandArgsError::UnknownOption(arg.to_string()));
+    } else if let Some((name, value)) = arg.split_once('=') {
+        (name, Some(value))
+    } else {
+        return Ok(None);
+    };
+
+    if VALUE_FLAGS.contains(&name) || SWITCH_FLAGS.contains(&name) {
+        Ok(Some((name, value)))
+    } else {
+        Err(CommandArgsError::UnknownOption(format!("--{}", name)))
+    }
+}
+
+/// Parses the arguments passed to the program
+///
+/// Flags can appear in any order, before, between or after the sources. When the first
+/// source is a file that starts with a shebang line, or after `--`, the remaining
+/// arguments are not parsed and are kept as the script arguments.
 ///
 /// # Arguments
-/// * `stack_size_arg` - String containing the argument to validate
+/// * `args` - Vector of program arguments
 ///
 /// # Example
 /// ```text
-/// use rust_the_forth::utils::validate_stack_size_arg;
-/// let validation = validate_stack_size_arg(&String::from("stack-size=10"));
+/// use std::env;
+/// use rust_the_forth::utils::cli_manager::parse_command_args;
+/// let args: Vec<String> = env::args().collect();
+/// let options = parse_command_args(&args)?;
 /// ```
-fn validate_stack_size_arg(stack_size_arg: &str) -> Result<(), CommandArgsError> {
-    let string_split: Vec<&str> = stack_size_arg.split('=').collect();
-
-    if string_split.len() >= 2 {
-        match string_split[1].parse::<i16>() {
-            Ok(size) => {
-                if convert_bytes_to_elements_amount(size) <= 0 {
-                    return Err(CommandArgsError::InvalidStackSize);
-                } else {
-                    return Ok(());
+pub fn parse_command_args(args: &[String]) -> Result<CliOptions, CommandArgsError> {
+    let mut options = CliOptions::default();
+    let mut seen_flags: Vec<&str> = Vec::new();
+    let mut args_iter = args.iter().skip(1);
+
+    while let Some(arg) = args_iter.next() {
+        if arg == PROGRAM_ARGS_SEPARATOR {
+            options.script_args = args_iter.by_ref().cloned().collect();
+            break;
+        }
+        if arg == STDIN_ARG {
+            options.sources.push(ProgramSource::Stdin);
+            continue;
+        }
+        if let Some(dir) = arg.strip_prefix(INCLUDE_DIR_FLAG) {
+            let dir = match dir {
+                "" => args_iter.next().map(String::as_str),
+                dir => Some(dir),
+            };
+            match dir {
+                Some(dir) if !dir.is_empty() => options.include_dirs.push(dir.to_string()),
+                _ => return Err(CommandArgsError::MissingValue(INCLUDE_DIR_FLAG.to_string())),
+            }
+            continue;
+        }
+        if arg == INLINE_CODE_FLAG {
+            let code = args_iter
+                .next()
+                .ok_or(CommandArgsError::MissingInlineCode)?;
+            options.sources.push(ProgramSource::Inline(code.clone()));
+            continue;
+        }
+        let (name, inline_value) = match split_flag(arg)? {
+            Some(flag) => flag,
+            None if arg.contains('.') => {
+                let is_script = options.sources.is_empty() && file_manager::has_shebang(arg);
+                options.sources.push(ProgramSource::File(arg.clone()));
+                if is_script {
+                    options.script_args = args_iter.by_ref().cloned().collect();
                 }
+                continue;
+            }
+            None => return Err(CommandArgsError::InvalidFileFormat),
+        };
+
+        if seen_flags.contains(&name) {
+            return Err(CommandArgsError::DuplicateOption(format!("--{}", name)));
+        }
+        seen_flags.push(name);
+
+        if SWITCH_FLAGS.contains(&name) {
+            if inline_value.is_some() {
+                return Err(CommandArgsError::UnexpectedValue(format!("--{}", name)));
             }
-            Err(_) => {
-                return Err(CommandArgsError::FailParseStackSize);
+            match name {
+                TRACE_FLAG => options.trace = true,
+                TRANSACTIONAL_FLAG => options.transactional = true,
+                NO_SAVE_FLAG => options.stack_file = None,
+                REPL_FLAG => options.repl = true,
+                HELP_FLAG => options.help = true,
+                _ => options.version = true,
             }
+            continue;
+        }
+
+        let value = match inline_value.or_else(|| args_iter.next().map(String::as_str)) {
+            Some(value) if !value.is_empty() => value,
+            _ => return Err(CommandArgsError::MissingValue(format!("--{}", name))),
+        };
+        match name {
+            STACK_SIZE_FLAG => options.stack_size = parse_stack_size(value)?,
+            ON_ERROR_FLAG => options.recovery_policy = Some(parse_recovery_policy(value)?),
+            FORMAT_FLAG => options.error_format = Some(parse_error_format(value)?),
+            OUTPUT_FLAG => options.stack_file = Some(value.to_string()),
+            LOAD_STACK_FLAG => options.load_stack = Some(value.to_string()),
+            LOAD_IMAGE_FLAG => options.load_image = Some(value.to_string()),
+            PRELUDE_FLAG => options.prelude = Some(value.to_string()),
+            _ => options.save_image = Some(value.to_string()),
         }
     }
 
-    Err(CommandArgsError::InvalidFormat)
+    check_conflicts(&seen_flags)?;
+    Ok(options)
+}
+
+/// Checks that no two flags that cannot be used together were passed
+///
+/// # Arguments
+/// * `flags` - Names of the flags that were passed
+fn check_conflicts(flags: &[&str]) -> Result<(), CommandArgsError> {
+    [
+        (LOAD_IMAGE_FLAG, LOAD_STACK_FLAG),
+        (LOAD_IMAGE_FLAG, STACK_SIZE_FLAG),
+        (OUTPUT_FLAG, NO_SAVE_FLAG),
+    ]
+    .iter()
+    .find(|(first, second)| flags.contains(first) && flags.contains(second))
+    .map_or(Ok(()), |(first, second)| {
+        Err(CommandArgsError::ConflictingOptions(
+            format!("--{}", first),
+            format!("--{}", second),
+        ))
+    })
 }
 
 /// Validates the arguments passed to the program
@@ -61,23 +504,10 @@ fn validate_stack_size_arg(stack_size_arg: &str) -> Result<(), CommandArgsError>
 /// let validation = validate_command_args(&args);
 /// ```
 pub fn validate_command_args(args: &[String]) -> Result<(), CommandArgsError> {
-    if args.len() <= 1 {
-        return Err(CommandArgsError::FileNotSpecified);
-    }
-
-    let arg_file = &args[1];
-    if !arg_file.contains(".") {
-        return Err(CommandArgsError::InvalidFileFormat);
-    }
-
-    if args.len() <= 2 {
-        return Ok(());
-    }
-
-    validate_stack_size_arg(&args[2])
+    parse_command_args(args).map(|_| ())
 }
 
-/// Returns the number of elements allowed by the `stack-size` argument
+/// Returns the number of elements allowed by the `--stack-size` argument
 ///
 /// # Arguments
 /// * `args` - Vector of program arguments
@@ -90,30 +520,52 @@ pub fn validate_command_args(args: &[String]) -> Result<(), CommandArgsError> {
 /// let vec_len = get_size_of_stack(&args);
 /// ```
 pub fn get_size_of_stack(args: &[String]) -> i16 {
-    let default_len = convert_bytes_to_elements_amount(128);
-    if args.len() <= 2 {
-        return default_len;
-    }
+    parse_command_args(args).unwrap_or_default().stack_size
+}
 
-    let string_split: Vec<&str> = args[2].split('=').collect();
+/// Returns the exit status that corresponds to an error
+///
+/// Errors reading or writing files (including invalid stack and image files) map to
+/// `EXIT_IO_ERROR`, every other error to `EXIT_RUNTIME_ERROR`.
+///
+/// # Arguments
+/// * `error` - The error that made the program fail
+pub fn exit_status_for(error: &OperationError) -> u8 {
+    match error {
+        OperationError::FailReadFile
+        | OperationError::FailWritingFile
+        | OperationError::FailWritingOutput
+        | OperationError::InvalidStackFile
+        | OperationError::InvalidImage => EXIT_IO_ERROR,
+        _ => EXIT_RUNTIME_ERROR,
+    }
+}
 
-    if string_split.len() >= 2 {
-        match string_split[1].parse::<i16>() {
-            Ok(size) => {
-                return convert_bytes_to_elements_amount(size);
-            }
-            Err(_) => {
-                return default_len;
-            }
-        }
+/// Returns the exit status of a program stopped by `bye` or `exit-code`
+///
+/// `bye` keeps the status of the errors reported before it, while `exit-code` replaces
+/// it. Like the operating system does, only the lowest 8 bits of the status are kept,
+/// so `-1 exit-code` exits with 255.
+///
+/// # Arguments
+/// * `halt` - How the program was stopped
+/// * `status` - The exit status before it was stopped
+pub fn exit_status_for_halt(halt: Halt, status: u8) -> u8 {
+    match halt {
+        Halt::Bye => status,
+        Halt::ExitCode(code) => code as u8,
     }
-    default_len
 }
 
 /// Tests unitarios
 #[cfg(test)]
 mod cli_manager_tests {
     use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
     #[test]
     fn test_convert_bytes_to_elements() {
         assert_eq!(convert_bytes_to_elements_amount(10), 5);
@@ -121,12 +573,12 @@ mod cli_manager_tests {
     }
 
     #[test]
-    fn test_validate_stack_size_arg() {
-        assert!(validate_stack_size_arg("stack-size=10").is_ok());
+    fn test_parse_stack_size() {
+        assert_eq!(parse_stack_size("10").ok(), Some(5));
 
-        assert!(validate_stack_size_arg("stack-size=0").is_err());
-        assert!(validate_stack_size_arg("stack-size=abc").is_err());
-        assert!(validate_stack_size_arg("formato-incorrecto").is_err());
+        assert!(parse_stack_size("0").is_err());
+        assert!(parse_stack_size("abc").is_err());
+        assert!(parse_stack_size("formato-incorrecto").is_err());
     }
 
     #[test]
@@ -156,4 +608,247 @@ mod cli_manager_tests {
         assert!(validate_command_args(&valid_args).is_ok());
         assert!(validate_command_args(&invalid_file_args).is_err());
     }
+
+    #[test]
+    fn test_flags_in_any_order() {
+        let options = parse_command_args(&to_args(&[
+            "program",
+            "--trace",
+            "lib.fth",
+            "--stack-size",
+            "64",
+            "-",
+            "--format=detailed",
+            "-e",
+            "1 2 = .",
+            "--on-error",
+            "line",
+            "--output",
+            "out.fth",
+        ]))
+        .ok()
+        .unwrap();
+
+        assert_eq!(
+            options.sources,
+            vec![
+                ProgramSource::File("lib.fth".to_string()),
+                ProgramSource::Stdin,
+                ProgramSource::Inline("1 2 = .".to_string()),
+            ]
+        );
+        assert_eq!(options.stack_size, 32);
+        assert_eq!(options.error_format, Some(ErrorFormat::Detailed));
+        assert_eq!(options.recovery_policy, Some(RecoveryPolicy::SkipLine));
+        assert_eq!(options.stack_file, Some("out.fth".to_string()));
+        assert!(options.trace);
+        assert!(!options.is_repl_mode());
+    }
+
+    #[test]
+    fn test_default_options() {
+        let options = parse_command_args(&to_args(&["program", "script.fth"]))
+            .ok()
+            .unwrap();
+        assert_eq!(
+            options,
+            CliOptions {
+                sources: vec![ProgramSource::File("script.fth".to_string())],
+                ..CliOptions::default()
+            }
+        );
+        assert_eq!(options.stack_file, Some("stack.fth".to_string()));
+        assert_eq!(options.recovery_policy, None);
+        assert_eq!(options.error_format, None);
+    }
+
+    #[test]
+    fn test_switch_flags() {
+        let options = parse_command_args(&to_args(&[
+            "program",
+            "--no-save",
+            "--help",
+            "--version",
+            "--repl",
+            "--transactional",
+            "script.fth",
+        ]))
+        .ok()
+        .unwrap();
+        assert_eq!(options.stack_file, None);
+        assert!(options.help && options.version && options.repl);
+        assert!(options.transactional);
+        assert!(options.is_repl_mode());
+
+        let options = parse_command_args(&to_args(&["program", "-h"]))
+            .ok()
+            .unwrap();
+        assert!(options.help);
+        assert!(options.is_repl_mode());
+    }
+
+    #[test]
+    fn test_legacy_key_value_options() {
+        let options = parse_command_args(&to_args(&[
+            "program",
+            "script.fth",
+            "on-error=continue",
+            "load-image=lib.img",
+            "save-image=out.img",
+        ]))
+        .ok()
+        .unwrap();
+        assert_eq!(options.recovery_policy, Some(RecoveryPolicy::Continue));
+        assert_eq!(options.load_image, Some("lib.img".to_string()));
+        assert_eq!(options.save_image, Some("out.img".to_string()));
+    }
+
+    #[test]
+    fn test_exit_status_for() {
+        assert_eq!(
+            exit_status_for(&OperationError::StackUnderflow),
+            EXIT_RUNTIME_ERROR
+        );
+        assert_eq!(
+            exit_status_for(&OperationError::FailReadFile),
+            EXIT_IO_ERROR
+        );
+    }
+
+    #[test]
+    fn test_arguments_after_a_script_are_passed_to_it() {
+        let path = std::env::temp_dir().join(format!("forth-args-{}.fth", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "#!/usr/bin/env rust-the-forth\n1 .").unwrap();
+
+        let options = parse_command_args(&to_args(&["prog", "--no-save", path, "a", "--trace"]));
+        std::fs::remove_file(path).unwrap();
+        let options = options.ok().unwrap();
+        assert_eq!(options.sources, vec![ProgramSource::File(path.to_string())]);
+        assert_eq!(options.script_args, to_args(&["a", "--trace"]));
+        assert!(!options.trace);
+        assert_eq!(options.program_args(), to_args(&[path, "a", "--trace"]));
+    }
+
+    #[test]
+    fn test_arguments_after_separator_are_passed_to_the_program() {
+        let options =
+            parse_command_args(&to_args(&["prog", "-e", "1", "--", "--trace", "x", "--"]))
+                .ok()
+                .unwrap();
+        assert_eq!(
+            options.sources,
+            vec![ProgramSource::Inline("1".to_string())]
+        );
+        assert_eq!(options.script_args, to_args(&["--trace", "x", "--"]));
+        assert!(!options.trace);
+        assert_eq!(
+            options.program_args(),
+            to_args(&["<inline>", "--trace", "x", "--"])
+        );
+        assert!(validate_command_args(&to_args(&["prog", "a.fth", "--", "b"])).is_ok());
+    }
+
+    #[test]
+    fn test_search_path_and_prelude() {
+        let options = parse_command_args(&to_args(&[
+            "prog",
+            "-I",
+            "lib",
+            "--prelude",
+            "std.fth",
+            "-Ivendor",
+            "main.fth",
+        ]))
+        .ok()
+        .unwrap();
+        assert_eq!(options.prelude, Some("std.fth".to_string()));
+        assert_eq!(options.include_dirs, to_args(&["lib", "vendor"]));
+
+        let forth_path = env::join_paths(["/usr/share/forth", "/opt/forth"]).unwrap();
+        assert_eq!(
+            options.search_path(Some(&forth_path)),
+            vec![
+                PathBuf::from("lib"),
+                PathBuf::from("vendor"),
+                PathBuf::from("/usr/share/forth"),
+                PathBuf::from("/opt/forth"),
+            ]
+        );
+        assert!(matches!(
+            parse_command_args(&to_args(&["prog", "-I"])),
+            Err(CommandArgsError::MissingValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_exit_status_for_halt() {
+        assert_eq!(exit_status_for_halt(Halt::Bye, EXIT_SUCCESS), EXIT_SUCCESS);
+        assert_eq!(
+            exit_status_for_halt(Halt::Bye, EXIT_RUNTIME_ERROR),
+            EXIT_RUNTIME_ERROR
+        );
+        assert_eq!(
+            exit_status_for_halt(Halt::ExitCode(0), EXIT_RUNTIME_ERROR),
+            EXIT_SUCCESS
+        );
+        assert_eq!(exit_status_for_halt(Halt::ExitCode(42), EXIT_SUCCESS), 42);
+        assert_eq!(exit_status_for_halt(Halt::ExitCode(-1), EXIT_SUCCESS), 255);
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        let parse = |args: &[&str]| parse_command_args(&to_args(args)).err();
+
+        assert!(matches!(
+            parse(&["program", "--colour", "red"]),
+            Some(CommandArgsError::UnknownOption(flag)) if flag == "--colour"
+        ));
+        assert!(matches!(
+            parse(&["program", "-x"]),
+            Some(CommandArgsError::UnknownOption(flag)) if flag == "-x"
+        ));
+        assert!(matches!(
+            parse(&["program", "--trace", "a.fth", "--trace"]),
+            Some(CommandArgsError::DuplicateOption(flag)) if flag == "--trace"
+        ));
+        assert!(matches!(
+            parse(&["program", "stack-size=64", "--stack-size=32"]),
+            Some(CommandArgsError::DuplicateOption(flag)) if flag == "--stack-size"
+        ));
+        assert!(matches!(
+            parse(&["program", "a.fth", "--output"]),
+            Some(CommandArgsError::MissingValue(flag)) if flag == "--output"
+        ));
+        assert!(matches!(
+            parse(&["program", "--trace=yes"]),
+            Some(CommandArgsError::UnexpectedValue(flag)) if flag == "--trace"
+        ));
+        assert!(matches!(
+            parse(&["program", "--format", "long"]),
+            Some(CommandArgsError::InvalidErrorFormat)
+        ));
+        assert!(matches!(
+            parse(&["program", "--on-error=never"]),
+            Some(CommandArgsError::InvalidRecoveryPolicy)
+        ));
+        assert!(matches!(
+            parse(&["program", "-e"]),
+            Some(CommandArgsError::MissingInlineCode)
+        ));
+        assert!(matches!(
+            parse(&["program", "--load-image=a.img", "--load-stack=s.fth"]),
+            Some(CommandArgsError::ConflictingOptions(first, second))
+                if first == "--load-image" && second == "--load-stack"
+        ));
+        assert!(matches!(
+            parse(&["program", "stack-size=64", "--load-image", "a.img"]),
+            Some(CommandArgsError::ConflictingOptions(first, second))
+                if first == "--load-image" && second == "--stack-size"
+        ));
+        assert!(matches!(
+            parse(&["program", "--output=s.fth", "--no-save"]),
+            Some(CommandArgsError::ConflictingOptions(_, _))
+        ));
+    }
 }
@@ -1,7 +1,55 @@
+use crate::core::error::OperationError;
 use std::fs::File;
 use std::io::{self, Read, Write};
+use std::ops::Range;
 use std::path::Path;
 
+/// Marker that starts the first line of an executable script.
+const SHEBANG: &str = "#!";
+
+/// Words followed by the name of a file, which is always read as a whole word.
+const FILE_NAME_WORDS: [&str; 2] = ["include", "require"];
+
+/// A literal whose text is kept in a single token, e.g. `." text"` or `s" text"`.
+struct Literal {
+    /// What the literal starts with, in lowercase. It must be followed by a whitespace.
+    start: &'static str,
+    /// The character that closes the literal.
+    end: char,
+    /// Whether `\` escapes the next character, so that `\"` does not close the literal.
+    escapes: bool,
+    /// Whether the literal is a string (`s"` or `s\"`) rather than printed text.
+    is_string: bool,
+}
+
+/// The literals recognized by the tokenizer. `s\"` comes before `s"`, which is a prefix of it.
+const LITERALS: [Literal; 4] = [
+    Literal {
+        start: ".\"",
+        end: '"',
+        escapes: true,
+        is_string: false,
+    },
+    Literal {
+        start: ".(",
+        end: ')',
+        escapes: false,
+        is_string: false,
+    },
+    Literal {
+        start: "s\\\"",
+        end: '"',
+        escapes: true,
+        is_string: true,
+    },
+    Literal {
+        start: "s\"",
+        end: '"',
+        escapes: false,
+        is_string: true,
+    },
+];
+
 // Reads the entire contents of a file into a `String`.
 ///
 /// # Arguments
@@ -18,6 +66,30 @@ pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
     Ok(content)
 }
 
+/// Checks if the file at `path` starts with a shebang (`#!`) line, i.e. it is meant to be
+/// run as an executable script.
+///
+/// # Examples
+/// ```text
+/// let is_script = file_manager::has_shebang("script.fth");
+/// ```
+pub fn has_shebang<P: AsRef<Path>>(path: P) -> bool {
+    let mut start = [0; SHEBANG.len()];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut start))
+        .is_ok_and(|_| start == SHEBANG.as_bytes())
+}
+
+/// Reads the whole standard input into a `String`.
+///
+/// # Examples
+/// ```text
+/// let content = file_manager::read_stdin()?;
+/// ```
+pub fn read_stdin() -> io::Result<String> {
+    io::read_to_string(io::stdin())
+}
+
 /// Writes the provided content to a file at the specified path.
 ///
 /// # Arguments
@@ -35,6 +107,9 @@ pub fn write_to_file<P: AsRef<Path>, C: AsRef<str>>(path: P, content: C) -> io::
     Ok(())
 }
 
+/// Name of the file where the CLI saves the stack by default.
+pub const DEFAULT_STACK_FILE: &str = "stack.fth";
+
 /// Saves the stack of numbers to a file named "stack.fth".
 ///
 /// The stack is represented as a slice of `i16` and the numbers are
@@ -48,20 +123,98 @@ pub fn write_to_file<P: AsRef<Path>, C: AsRef<str>>(path: P, content: C) -> io::
 /// file_manager::save_stack(&[1, 2, 3])?;
 /// ```
 pub fn save_stack(stack: &[i16]) -> io::Result<()> {
-    let stack_str = stack
+    save_stack_to(DEFAULT_STACK_FILE, stack)
+}
+
+/// Saves the stack of numbers to the file at the specified path.
+///
+/// # Arguments
+/// * `path` - A value that can be referenced as a path.
+/// * `stack` - A slice of `i16` integers representing the stack.
+///
+/// # Examples
+/// ``` text
+/// file_manager::save_stack_to("out/stack.fth", &[1, 2, 3])?;
+/// ```
+pub fn save_stack_to<P: AsRef<Path>>(path: P, stack: &[i16]) -> io::Result<()> {
+    write_to_file(path, format_stack(stack))
+}
+
+/// Reads a stack previously saved with `save_stack` or `save_stack_to`.
+///
+/// # Arguments
+/// * `path` - A value that can be referenced as a path.
+///
+/// # Returns
+/// * `Err(OperationError::FailReadFile)` if the file cannot be read.
+/// * `Err(OperationError::InvalidStackFile)` if it contains something other than numbers.
+///
+/// # Examples
+/// ``` text
+/// let stack = file_manager::load_stack("stack.fth")?; // [1, 2, 3]
+/// ```
+pub fn load_stack<P: AsRef<Path>>(path: P) -> Result<Vec<i16>, OperationError> {
+    let content = read_to_string(path).map_err(|_| OperationError::FailReadFile)?;
+    parse_stack(&content)
+}
+
+/// Parses a space-separated string of numbers, the format written by `save_stack`.
+///
+/// # Examples
+/// ``` text
+/// assert_eq!(file_manager::parse_stack("1 -2 3"), Ok(vec![1, -2, 3]));
+/// ```
+pub fn parse_stack(content: &str) -> Result<Vec<i16>, OperationError> {
+    content
+        .split_whitespace()
+        .map(|number| {
+            number
+                .parse::<i16>()
+                .map_err(|_| OperationError::InvalidStackFile)
+        })
+        .collect()
+}
+
+/// Formats the stack as a space-separated string of numbers.
+///
+/// # Examples
+/// ``` text
+/// assert_eq!(file_manager::format_stack(&[1, 2, 3]), "1 2 3");
+/// ```
+pub fn format_stack(stack: &[i16]) -> String {
+    stack
         .iter()
         .map(|n| n.to_string())
         .collect::<Vec<String>>()
-        .join(" ");
+        .join(" ")
+}
+
+/// Position of a token inside its source, both values starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    /// Line where the token starts.
+    pub line: usize,
+    /// Column (in characters) where the token starts.
+    pub column: usize,
+}
 
-    write_to_file("stack.fth", stack_str)
+/// A token together with the position where it starts in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceToken {
+    /// The text of the token.
+    pub text: String,
+    /// Where the token starts.
+    pub position: SourcePosition,
+    /// Indices of the characters the token spans in the source.
+    pub span: Range<usize>,
 }
 
 /// Tokenizes the input string into a vector of tokens.
 ///
 /// The function splits the input string by whitespace and also supports
-/// tokens that begin with a literal marker (`."`) followed by text enclosed
-/// in double quotes.
+/// literals that are kept in a single token: `." text"`, `.( text)`, `s" text"`
+/// and `s\" text"`. A literal that is never closed takes the rest of the input. The
+/// word after `include` or `require` is a file name, so it is never split.
 ///
 /// # Arguments
 /// * `input` - The string input to tokenize.
@@ -72,82 +225,265 @@ pub fn save_stack(stack: &[i16]) -> io::Result<()> {
 /// // tokens: ["Hello", ".\" world\""]
 /// ```
 pub fn tokenize(input: &str) -> Vec<String> {
+    tokenize_with_positions(input)
+        .into_iter()
+        .map(|token| token.text)
+        .collect()
+}
+
+/// Tokenizes the input string like [`tokenize`], keeping the line and column
+/// where every token starts.
+///
+/// # Arguments
+/// * `input` - The string input to tokenize.
+///
+/// # Examples
+/// ``` text
+/// let tokens = file_manager::tokenize_with_positions("1 2\n+");
+/// // tokens[2]: SourceToken { text: "+", position: SourcePosition { line: 2, column: 1 } }
+/// ```
+pub fn tokenize_with_positions(input: &str) -> Vec<SourceToken> {
+    tokenize_from_position(input, SourcePosition { line: 1, column: 1 })
+}
+
+/// Tokenizes the input string like [`tokenize_with_positions`], for an input that
+/// starts at `start` inside a larger source (e.g. a chunk fed to the interpreter).
+///
+/// A shebang (`#!`) line at the very beginning of a source is skipped, so executable
+/// scripts can be run directly.
+///
+/// # Arguments
+/// * `input` - The string input to tokenize.
+/// * `start` - Position of the first character of `input`.
+pub fn tokenize_from_position(input: &str, start: SourcePosition) -> Vec<SourceToken> {
     let chars: Vec<char> = input.chars().collect();
     let mut tokens = Vec::new();
+    let mut position = start;
+    let mut last_index = 0;
     let mut i = 0;
 
+    if start == (SourcePosition { line: 1, column: 1 }) && input.starts_with(SHEBANG) {
+        while i < chars.len() && chars[i] != '\n' {
+            i += 1;
+        }
+    }
+
     while i < chars.len() {
         if chars[i].is_whitespace() {
             i += 1;
             continue;
         }
-        if chars[i] == '.' {
-            if is_print_text_format_valid(&chars, &mut i) {
-                process_print_text_operation(&chars, &mut i, &mut tokens);
-                continue;
-            } else {
-                tokens.push(".".to_string());
-                i += 1;
-                continue;
-            }
-        }
+        advance_position(&chars, last_index, i, &mut position);
+        last_index = i;
 
-        let start = i;
-        while i < chars.len() && !chars[i].is_whitespace() {
+        let is_file_name = tokens.last().is_some_and(|token: &SourceToken| {
+            FILE_NAME_WORDS.contains(&token.text.to_lowercase().as_str())
+        });
+        let text = if is_file_name {
+            read_word(&chars, &mut i)
+        } else if let Some(literal) = find_literal(&chars[i..]) {
+            let end = find_literal_end(&chars[i..], literal).map_or(chars.len(), |end| i + end + 1);
+            let text = chars[i..end].iter().collect();
+            i = end;
+            text
+        } else if chars[i] == '.' && matches!(chars.get(i + 1), Some('"') | Some('(')) {
             i += 1;
-        }
-        tokens.push(chars[start..i].iter().collect());
+            ".".to_string()
+        } else {
+            read_word(&chars, &mut i)
+        };
+        tokens.push(SourceToken {
+            text,
+            position,
+            span: last_index..i,
+        });
     }
     tokens
 }
 
-/// Checks if the current character position matches the expected start of a print text operation
-/// in the format: ."<space>
-/// # Arguments
-/// * `chars` - A reference to the vector of characters being parsed
-/// * `i` - A mutable reference to the current index in the character vector
-/// # Example
+/// Reads the characters from `i` up to the next whitespace, leaving `i` after them.
+fn read_word(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && !chars[*i].is_whitespace() {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+/// Returns the position right after the end of `input`, when `input` starts at `start`.
+///
+/// # Examples
+/// ``` text
+/// let end = file_manager::position_after("1 2\n+", SourcePosition { line: 1, column: 1 });
+/// // end: SourcePosition { line: 2, column: 2 }
+/// ```
+pub fn position_after(input: &str, start: SourcePosition) -> SourcePosition {
+    let chars: Vec<char> = input.chars().collect();
+    let mut position = start;
+    advance_position(&chars, 0, chars.len(), &mut position);
+    position
+}
+
+/// Moves `position` forward over the characters in `chars[from..to]`.
+fn advance_position(chars: &[char], from: usize, to: usize, position: &mut SourcePosition) {
+    for ch in &chars[from..to] {
+        if *ch == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    }
+}
+
+/// Returns the text of a string literal token (`s" text"` or `s\" text"`), with the
+/// escape sequences of `s\"` already replaced, or `None` if `token` is not a closed one.
+///
+/// # Examples
 /// ```text
-/// let chars: Vec<char> = ".\" Hello\"".chars().collect();
-/// let mut index = 0;
-/// assert!(is_print_text_format_valid(&chars, &mut index));
+/// assert_eq!(file_manager::parse_string_literal("S\" Hello\""), Some("Hello".to_string()));
+/// assert_eq!(file_manager::parse_string_literal("s\\\" a\\tb\""), Some("a\tb".to_string()));
 /// ```
-fn is_print_text_format_valid(chars: &[char], i: &mut usize) -> bool {
-    *i + 2 < chars.len() && chars[*i..*i + 3] == ['.', '"', ' ']
+pub fn parse_string_literal(token: &str) -> Option<String> {
+    let chars: Vec<char> = token.chars().collect();
+    let literal = find_literal(&chars).filter(|literal| literal.is_string)?;
+    literal_text(&chars, literal)
 }
 
-/// Processes a print text operation starting at the current index and appends the corresponding
-/// token to the tokens vector.
+/// Returns the text printed by a `." text"` or `.( text)` token, or `None` if `token` is
+/// not a closed one. Escape sequences of `."` are already replaced.
 ///
-/// This function assumes that the `.\" ` format has already been validated.
-/// It will collect characters until the closing `"` is found and build a formatted string token.
+/// As for `."`, the text keeps the whitespace that follows the opening `"`; for `.(`
+/// the text starts right after it.
 ///
-/// # Arguments
-/// * `chars` - A reference to the vector of characters being parsed
-/// * `i` - A mutable reference to the current index in the character vector
-/// * `tokens` - A mutable reference to the vector where the resulting token will be stored
+/// # Examples
+/// ```text
+/// assert_eq!(file_manager::parse_print_literal(".\" a\\tb\""), Some(" a\tb".to_string()));
+/// assert_eq!(file_manager::parse_print_literal(".( hello)"), Some("hello".to_string()));
+/// ```
+pub fn parse_print_literal(token: &str) -> Option<String> {
+    let chars: Vec<char> = token.chars().collect();
+    let literal = find_literal(&chars).filter(|literal| !literal.is_string)?;
+    let text = literal_text(&chars, literal)?;
+    if literal.start == ".\"" {
+        let delimiter = chars[literal.start.len()];
+        Some(format!("{}{}", delimiter, text))
+    } else {
+        Some(text)
+    }
+}
+
+/// Checks if `token` is a literal (`."`, `.(`, `s"` or `s\"`), closed or not.
+pub fn is_literal(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().collect();
+    find_literal(&chars).is_some()
+}
+
+/// Checks if `token` is a literal that is missing its closing delimiter.
 ///
-/// # Example
+/// # Examples
 /// ```text
-/// let chars: Vec<char> = ".\" Hello world\"".chars().collect();
-/// let mut index = 0;
-/// let mut tokens = vec![];
-/// if is_print_text_format_valid(&chars, &mut index) {
-///     process_print_text_operation(&chars, &mut index, &mut tokens);
-/// }
-/// assert_eq!(tokens, vec![".\" Hello world\""]);
-/// ```
-fn process_print_text_operation(chars: &[char], i: &mut usize, tokens: &mut Vec<String>) {
-    let mut literal = String::new();
-    literal.push(' ');
-    *i += 3;
-
-    while *i < chars.len() && chars[*i] != '"' {
-        literal.push(chars[*i]);
-        *i += 1;
+/// assert!(file_manager::is_unterminated_literal(".\" hello"));
+/// assert!(file_manager::is_unterminated_literal(".\" hello\\\""));
+/// assert!(!file_manager::is_unterminated_literal(".\" hello\""));
+/// ```
+pub fn is_unterminated_literal(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().collect();
+    find_literal(&chars)
+        .is_some_and(|literal| find_literal_end(&chars, literal) != Some(chars.len() - 1))
+}
+
+/// Replaces the escape sequences of `s\"` in `text`: `\a`, `\b`, `\e`, `\f`, `\l`,
+/// `\m` (CR LF), `\n`, `\q` and `\"` (a quote), `\r`, `\t`, `\v`, `\z` (NUL), `\\` and
+/// `\xHH` (the character with hexadecimal code `HH`). Unknown sequences are kept as written.
+///
+/// # Examples
+/// ```text
+/// assert_eq!(file_manager::unescape("a\\nb\\x41"), "a\nbA");
+/// ```
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        let replacement = match chars.peek() {
+            Some('a') => "\u{7}",
+            Some('b') => "\u{8}",
+            Some('e') => "\u{1b}",
+            Some('f') => "\u{c}",
+            Some('l') | Some('n') => "\n",
+            Some('m') => "\r\n",
+            Some('q') | Some('"') => "\"",
+            Some('r') => "\r",
+            Some('t') => "\t",
+            Some('v') => "\u{b}",
+            Some('z') => "\0",
+            Some('\\') => "\\",
+            Some('x') => {
+                let digits: String = chars.clone().skip(1).take(2).collect();
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(code) if digits.len() == 2 => {
+                        chars.nth(2);
+                        unescaped.push(char::from(code));
+                    }
+                    _ => unescaped.push(ch),
+                }
+                continue;
+            }
+            _ => {
+                unescaped.push(ch);
+                continue;
+            }
+        };
+        chars.next();
+        unescaped.push_str(replacement);
     }
-    if *i < chars.len() && chars[*i] == '"' {
-        *i += 1;
+    unescaped
+}
+
+/// Returns the literal that `chars` begins with, if any. The start of a literal is
+/// case insensitive and must be followed by a whitespace.
+fn find_literal(chars: &[char]) -> Option<&'static Literal> {
+    LITERALS.iter().find(|literal| {
+        let len = literal.start.len();
+        chars.len() > len
+            && chars[len].is_whitespace()
+            && literal
+                .start
+                .chars()
+                .zip(chars)
+                .all(|(expected, ch)| ch.to_ascii_lowercase() == expected)
+    })
+}
+
+/// Returns the index in `chars` of the delimiter that closes `literal`, which `chars`
+/// begins with, or `None` if the literal is never closed.
+fn find_literal_end(chars: &[char], literal: &Literal) -> Option<usize> {
+    let mut i = literal.start.len() + 1;
+    while i < chars.len() {
+        if literal.escapes && chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == literal.end {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the text between the delimiters of a closed literal, with its escape sequences
+/// replaced.
+fn literal_text(chars: &[char], literal: &Literal) -> Option<String> {
+    let end = find_literal_end(chars, literal).filter(|end| *end == chars.len() - 1)?;
+    let text: String = chars[literal.start.len() + 1..end].iter().collect();
+    if literal.escapes {
+        Some(unescape(&text))
+    } else {
+        Some(text)
     }
-    tokens.push(format!(".\"{}\"", literal));
 }
@@ -8,6 +8,13 @@
 /// receive informative messages when arguments are incorrect or missing.
 pub mod cli_manager;
 
+/// Provides the interactive read-eval-print loop.
+///
+/// This module defines the `Repl` type, which evaluates Forth code line by line over a
+/// single interpreter and answers each line with ` ok` or the error, reading from any
+/// buffered reader and writing to any writer.
+pub mod repl;
+
 /// Provides file input/output operations for the Forth interpreter.
 ///
 /// This module manages reading Forth code from a file and saving the final state of the
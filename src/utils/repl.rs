@@ -0,0 +1,136 @@
+use crate::core::error::ForthError;
+use crate::core::forth_calculator::ForthCalculator;
+use crate::core::output_sink::OutputSink;
+use crate::core::run_result::{Halt, RunResult};
+use std::io::{self, BufRead, Write};
+
+/// Source name used in error messages for the lines typed in the REPL.
+const REPL_SOURCE_NAME: &str = "<stdin>";
+
+/// Answer printed after a line that ran without errors.
+const OK_MESSAGE: &str = " ok\n";
+
+/// Answer printed after a line that leaves a definition or a literal unfinished.
+const COMPILED_MESSAGE: &str = " compiled\n";
+
+/// A line-oriented interactive session over a `ForthCalculator`.
+///
+/// Every line is evaluated as soon as it is read, keeping the stack and the word
+/// definitions between lines. After each line the REPL prints the output it produced
+/// followed by ` ok`, or by the error message if the line failed. Lines are fed to the
+/// calculator incrementally, so a colon definition can span several lines. The session
+/// ends when the input does, reporting anything left unfinished, or when a line runs
+/// `bye` or `exit-code`.
+///
+/// # Example
+/// ```text
+/// let mut repl = Repl::new(ForthCalculator::new(128));
+/// repl.run("1 2 + .\n".as_bytes(), io::stdout())?; // prints "3  ok"
+/// ```
+pub struct Repl {
+    /// The interpreter whose state persists across lines.
+    calculator: ForthCalculator,
+    /// How `bye` or `exit-code` stopped the session, once a line halted.
+    halted: Option<Halt>,
+    /// The errors reported by every line so far.
+    errors: Vec<ForthError>,
+}
+
+impl Repl {
+    /// Creates a REPL over `calculator`.
+    ///
+    /// The output and the errors of the calculator are kept in memory so the REPL can
+    /// print them after each line. The recovery policy and the transactional mode of the
+    /// calculator decide what happens with the rest of a line that fails.
+    pub fn new(mut calculator: ForthCalculator) -> Self {
+        calculator.set_source_name(REPL_SOURCE_NAME);
+        calculator.set_output(OutputSink::default());
+        calculator.set_error_output(OutputSink::default());
+        Repl {
+            calculator,
+            halted: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Returns the interpreter, e.g. to inspect its stack.
+    pub fn calculator(&self) -> &ForthCalculator {
+        &self.calculator
+    }
+
+    /// Returns the interpreter, e.g. to register native words.
+    pub fn calculator_mut(&mut self) -> &mut ForthCalculator {
+        &mut self.calculator
+    }
+
+    /// Returns how `bye` or `exit-code` stopped the session, if a line halted it.
+    pub fn halted(&self) -> Option<Halt> {
+        self.halted
+    }
+
+    /// Returns the errors reported by every line evaluated so far, in order.
+    pub fn errors(&self) -> &[ForthError] {
+        &self.errors
+    }
+
+    /// Evaluates a single line and returns the text to print for it.
+    ///
+    /// # Returns
+    /// The output of the line followed by the error messages, ` compiled` if the line
+    /// leaves a definition unfinished, or ` ok`. A line that halts the session only
+    /// gets its output.
+    pub fn eval_line(&mut self, line: &str) -> String {
+        let result = self.calculator.feed(&format!("{}\n", line));
+        self.answer(result)
+    }
+
+    /// Evaluates whatever the previous lines left unfinished, as if the input ended
+    /// there, and returns the text to print for it.
+    ///
+    /// # Returns
+    /// The same answer as `eval_line`; an unfinished definition or literal is reported
+    /// as an error.
+    pub fn finish(&mut self) -> String {
+        let result = self.calculator.finish();
+        self.answer(result)
+    }
+
+    /// Builds the text to print after evaluating a line.
+    fn answer(&mut self, result: RunResult) -> String {
+        let failed = !result.is_ok();
+        self.errors.extend(result.errors);
+        let mut answer = result.output;
+        if result.halted.is_some() {
+            self.halted = result.halted;
+        } else if failed {
+            answer.push_str(self.calculator.get_error_output());
+        } else if self.calculator.has_pending_input() {
+            answer.push_str(COMPILED_MESSAGE);
+        } else {
+            answer.push_str(OK_MESSAGE);
+        }
+        answer
+    }
+
+    /// Reads lines from `input` until it ends or a line halts the session, writing the answer to each of them to
+    /// `output`. When the input ends in the middle of a construct, the error it causes is
+    /// written too.
+    ///
+    /// # Returns
+    /// * `Err` if reading the input or writing the output fails.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let answer = self.eval_line(&line?);
+            output.write_all(answer.as_bytes())?;
+            output.flush()?;
+            if self.halted.is_some() {
+                return Ok(());
+            }
+        }
+        if self.calculator.has_pending_input() {
+            output.write_all(self.finish().as_bytes())?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust-the-forth"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run_cli_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-the-forth"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_successful_run_exits_with_zero() {
+    let output = run_cli(&["--no-save", "-e", "1 2 + ."]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3 ");
+}
+
+#[test]
+fn test_runtime_error_exit_status() {
+    let output = run_cli(&["--no-save", "-e", "1 +"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "stack-underflow\n");
+}
+
+#[test]
+fn test_argument_error_exit_status() {
+    let output = run_cli(&["--unknown"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Unknown option: --unknown (see --help)\n"
+    );
+    assert_eq!(run_cli(&["--stack-size", "abc"]).status.code(), Some(2));
+
+    let output = run_cli(&["--no-save", "--load-image", "missing.img", "-e", "1 ."]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn test_missing_file_exit_status() {
+    let output = run_cli(&["--no-save", "missing-file.fth"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "missing-file.fth: We have a problem with reading the file\n"
+    );
+}
+
+#[test]
+fn test_missing_file_keeps_the_stack_saved_by_previous_sources() {
+    let path = std::env::temp_dir().join(format!("forth-kept-stack-{}.fth", std::process::id()));
+    let path = path.to_str().unwrap();
+    let output = run_cli(&["--output", path, "-e", "1 2", "missing-file.fth", "-e", "3"]);
+    let saved = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(saved, "1 2");
+}
+
+#[test]
+fn test_help_and_version_exit_with_zero() {
+    let output = run_cli(&["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage:"));
+    assert_eq!(run_cli(&["--version"]).status.code(), Some(0));
+}
+
+#[test]
+fn test_bye_and_exit_code_set_the_exit_status() {
+    let output = run_cli(&["--no-save", "-e", "1 . bye 2 .", "-e", "3 ."]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1 ");
+
+    let output = run_cli(&["--no-save", "-e", "1 +", "-e", "42 exit-code"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_cli(&[
+        "--no-save",
+        "--on-error",
+        "continue",
+        "-e",
+        "1 + 42 exit-code",
+    ]);
+    assert_eq!(output.status.code(), Some(42));
+
+    let output = run_cli(&[
+        "--no-save",
+        "--on-error",
+        "continue",
+        "-e",
+        "drop",
+        "-e",
+        "bye",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_cli(&["--no-save", "-e", "drop 0 exit-code"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_repl_errors_set_the_exit_status() {
+    let output = run_cli_with_input(&["--no-save"], "1 +\n2 .\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "stack-underflow\n2  ok\n"
+    );
+
+    let output = run_cli_with_input(&["--no-save"], "1 +\n3 exit-code\n");
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run_cli_with_input(&["--no-save"], "1 2 + .\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn test_shebang_script_receives_the_remaining_arguments() {
+    let path = std::env::temp_dir().join(format!("forth-script-{}.fth", std::process::id()));
+    std::fs::write(
+        &path,
+        "#!/usr/bin/env rust-the-forth\nargc . 1 arg swap drop . 2 arg swap drop .",
+    )
+    .unwrap();
+
+    let output = run_cli(&["--no-save", path.to_str().unwrap(), "hello", "--trace"]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3 5 7 ");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_arguments_after_separator_reach_the_program() {
+    let output = run_cli(&[
+        "--no-save",
+        "-e",
+        "argc . next-arg swap drop . next-arg swap drop . next-arg + .",
+        "--",
+        "abc",
+        "--no-save",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3 3 9 0 ");
+}
+
+#[test]
+fn test_prelude_and_search_path() {
+    let dir = std::env::temp_dir().join(format!("forth-search-path-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::create_dir_all(dir.join("shared")).unwrap();
+    std::fs::write(dir.join("lib/prelude.fth"), "require square.fth").unwrap();
+    std::fs::write(dir.join("shared/square.fth"), ": square dup * ;").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-the-forth"))
+        .args(["--no-save", "-I", dir.join("lib").to_str().unwrap()])
+        .args(["--prelude", "prelude.fth", "-e", "3 square ."])
+        .env("FORTH_PATH", dir.join("shared"))
+        .output()
+        .unwrap();
+    let missing = run_cli(&["--no-save", "--prelude", "missing.fth", "-e", "1 ."]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "9 ");
+    assert_eq!(missing.status.code(), Some(3));
+    assert!(missing.stdout.is_empty());
+}
+
+#[test]
+fn test_transactional_flag_rolls_back_failing_sources() {
+    let args = [
+        "--no-save",
+        "--on-error",
+        "continue",
+        "-e",
+        "7",
+        "-e",
+        "1 + 0 0 /",
+        "-e",
+        ".",
+    ];
+    let output = run_cli(&args);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "8 ");
+
+    let output = run_cli(&[&["--transactional"], &args[..]].concat());
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "7 ");
+}
+
+#[test]
+fn test_image_keeps_its_settings_unless_they_are_passed() {
+    let path = std::env::temp_dir().join(format!("forth-settings-{}.img", std::process::id()));
+    let path = path.to_str().unwrap();
+    let output = run_cli(&[
+        "--no-save",
+        "--on-error",
+        "continue",
+        "--format",
+        "detailed",
+        "-e",
+        "",
+        "--save-image",
+        path,
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let kept = run_cli(&["--no-save", "--load-image", path, "-e", "drop 5 ."]);
+    let passed = run_cli(&[
+        "--no-save",
+        "--load-image",
+        path,
+        "--on-error",
+        "halt",
+        "--format",
+        "short",
+        "-e",
+        "drop 5 .",
+    ]);
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(kept.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&kept.stdout), "5 ");
+    assert_eq!(
+        String::from_utf8_lossy(&kept.stderr),
+        "stack-underflow: 'drop' at <inline>:1:1\n"
+    );
+    assert_eq!(passed.status.code(), Some(1));
+    assert!(passed.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&passed.stderr), "stack-underflow\n");
+}
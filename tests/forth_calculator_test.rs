@@ -1,4 +1,11 @@
+use rust_the_forth::core::error::{ErrorFormat, OperationError, RecoveryPolicy};
 use rust_the_forth::core::forth_calculator::ForthCalculator;
+use rust_the_forth::core::output_sink::OutputSink;
+use rust_the_forth::core::run_result::Halt;
+use rust_the_forth::core::stack_persistence::StackPersistence;
+use rust_the_forth::utils::file_manager;
+use std::cell::RefCell;
+use std::rc::Rc;
 const DEFAULT_STACK_SIZE: i16 = 128;
 
 fn create_calculator(stack_size: i16) -> ForthCalculator {
@@ -151,17 +158,17 @@ fn test_output_operations() {
 
     calculator.run("42 .".to_string());
     assert_eq!(calculator.get_stack().clone(), vec![]);
-    assert_eq!(calculator.get_output().clone(), "42 ");
+    assert_eq!(calculator.get_output(), "42 ");
     calculator = create_calculator(DEFAULT_STACK_SIZE);
 
     calculator.run("1 2 cr".to_string());
     assert_eq!(calculator.get_stack().clone(), vec![1, 2]);
-    assert_eq!(calculator.get_output().clone(), "\n");
+    assert_eq!(calculator.get_output(), "\n");
     calculator = create_calculator(DEFAULT_STACK_SIZE);
 
     calculator.run("65 emit".to_string());
     assert_eq!(calculator.get_stack().clone(), vec![]);
-    assert_eq!(calculator.get_output().clone(), "A ");
+    assert_eq!(calculator.get_output(), "A ");
     calculator = create_calculator(DEFAULT_STACK_SIZE);
 
     calculator.run(".\" Hello, world!\"".to_string());
@@ -508,3 +515,955 @@ fn test_errors() {
         5,
     );
 }
+
+fn eval_detailed_error(code: &str) -> String {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+    calc.set_source_name("script.fth");
+    calc.run(code.to_string());
+    calc.get_output().to_string()
+}
+
+#[test]
+fn test_detailed_error_top_level_token() {
+    assert_eq!(
+        eval_detailed_error("1 2 +\n  +"),
+        "stack-underflow: '+' at script.fth:2:3\n"
+    );
+    assert_eq!(eval_detailed_error("1 foo"), "?: 'foo' at script.fth:1:3\n");
+}
+
+#[test]
+fn test_detailed_error_call_trace() {
+    let code = ": square dup * ;\n: main square ;\n\n    main";
+    assert_eq!(
+        eval_detailed_error(code),
+        "stack-underflow: 'dup' in 'square' called from 'main' at script.fth:4:5\n"
+    );
+    assert_eq!(
+        eval_detailed_error(": f if 1 0 / then ; -1 f"),
+        "division-by-zero: '/' in 'f' at script.fth:1:24\n"
+    );
+}
+
+#[test]
+fn test_short_error_format_is_default() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.run(": square dup * ; square".to_string());
+    assert_eq!(calc.get_output(), "stack-underflow\n");
+}
+
+fn eval_with_policy(code: &str, recovery_policy: RecoveryPolicy) -> ForthCalculator {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_recovery_policy(recovery_policy);
+    calc.run(code.to_string());
+    calc
+}
+
+#[test]
+fn test_continue_policy_runs_every_token() {
+    let calc = eval_with_policy("+ 1 .\ndrop 2 .\n3", RecoveryPolicy::Continue);
+    assert_eq!(calc.get_output(), "stack-underflow\n1 stack-underflow\n2 ");
+    assert_eq!(calc.get_stack(), &vec![3]);
+}
+
+#[test]
+fn test_halt_policy_stops_at_first_error() {
+    let calc = eval_with_policy("1 2 . + 3\n4", RecoveryPolicy::Halt);
+    assert_eq!(calc.get_output(), "2 stack-underflow\n");
+    assert!(calc.get_stack().is_empty());
+}
+
+#[test]
+fn test_skip_line_policy_resumes_on_next_line() {
+    let calc = eval_with_policy("1 + 2\n3 4 + .\n0 / 5\n6", RecoveryPolicy::SkipLine);
+    assert_eq!(calc.get_output(), "stack-underflow\n7 stack-underflow\n");
+    assert_eq!(calc.get_stack(), &vec![6]);
+}
+
+#[test]
+fn test_unknown_words_follow_the_recovery_policy() {
+    let calc = eval_with_policy("1 .\nfoo 3\n2 .", RecoveryPolicy::Continue);
+    assert_eq!(calc.get_output(), "1 ?\n2 ");
+    assert_eq!(calc.get_stack(), &vec![3]);
+
+    let calc = eval_with_policy("1 .\nfoo 3\n2 .", RecoveryPolicy::SkipLine);
+    assert_eq!(calc.get_output(), "1 ?\n2 ");
+    assert!(calc.get_stack().is_empty());
+
+    let calc = eval_with_policy("1 .\nfoo 3\n2 .", RecoveryPolicy::Halt);
+    assert_eq!(calc.get_output(), "1 ?\n");
+    assert!(calc.get_stack().is_empty());
+}
+
+#[test]
+fn test_transactional_run_restores_stack_and_words() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_transactional(true);
+    calc.run(": foo 1 ; 1 2".to_string());
+
+    calc.run(": foo 2 ; : bar 3 ; 3 drop drop drop drop".to_string());
+    assert_eq!(calc.get_output(), "stack-underflow\n");
+    assert_eq!(calc.get_stack(), &vec![1, 2]);
+
+    calc.run("foo".to_string());
+    assert_eq!(calc.get_stack(), &vec![1, 2, 1]);
+
+    calc.run("bar".to_string());
+    assert_eq!(calc.get_output(), "?\n");
+    assert_eq!(calc.get_stack(), &vec![1, 2, 1]);
+}
+
+#[test]
+fn test_transactional_run_restores_the_data_space() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_transactional(true);
+    calc.run("s\" abc\"".to_string());
+
+    let result = calc.evaluate("over 90 swap c! s\" xyz\" drop drop drop drop drop");
+    assert_eq!(result.errors[0].error, OperationError::StackUnderflow);
+    assert_eq!(calc.get_data_space().as_bytes(), b"abc");
+    assert_eq!(calc.get_stack(), &vec![0, 3]);
+}
+
+#[test]
+fn test_non_transactional_run_keeps_partial_changes() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.run(": foo 2 ; 1 2 3 + + +".to_string());
+    assert_eq!(calc.get_stack(), &vec![]);
+
+    calc.run("foo".to_string());
+    assert_eq!(calc.get_stack(), &vec![2]);
+}
+
+#[test]
+fn test_output_is_streamed_to_callback_sink() {
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+    let chunks_in_sink = Rc::clone(&chunks);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_output(OutputSink::callback(move |text| {
+        chunks_in_sink.borrow_mut().push(text.to_string())
+    }));
+
+    calc.run("1 . 65 emit cr .\" done\" +".to_string());
+    assert_eq!(
+        *chunks.borrow(),
+        vec!["1 ", "A ", "\n", " done", "stack-underflow\n"]
+    );
+    assert_eq!(calc.get_output(), "");
+}
+
+#[test]
+fn test_errors_go_to_separate_channel() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_output(OutputSink::default());
+
+    calc.run("1 . + 2 .\n5 0 /".to_string());
+    assert_eq!(calc.get_output(), "1 2 ");
+    assert_eq!(
+        calc.get_error_output(),
+        "stack-underflow\ndivision-by-zero\n"
+    );
+
+    let errors: Vec<&OperationError> = calc.get_errors().iter().map(|e| &e.error).collect();
+    assert_eq!(
+        errors,
+        vec![
+            &OperationError::StackUnderflow,
+            &OperationError::DivisionByZero
+        ]
+    );
+    assert_eq!(calc.get_errors()[1].position.map(|p| p.line), Some(2));
+}
+
+#[test]
+fn test_errors_are_interleaved_by_default() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.run("1 . + 2 .".to_string());
+    assert_eq!(calc.get_output(), "1 stack-underflow\n2 ");
+    assert_eq!(calc.get_error_output(), "");
+    assert_eq!(calc.get_errors().len(), 1);
+
+    calc.run("1 .".to_string());
+    assert!(calc.get_errors().is_empty());
+}
+
+#[test]
+fn test_evaluate_returns_structured_result() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(": square dup * ; : cube dup square * ; 2 cube . 3 square");
+
+    assert_eq!(result.stack, vec![9]);
+    assert_eq!(result.output, "8 ");
+    assert!(result.is_ok());
+    assert_eq!(result.defined_words, vec!["square", "cube"]);
+    assert_eq!(result.stats.words_called, 3);
+    assert_eq!(result.stats.tokens_executed, 12);
+    assert_eq!(result.stats.max_stack_depth, 3);
+}
+
+#[test]
+fn test_evaluate_reports_errors_with_positions() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("1 2\n+ + +");
+
+    assert!(!result.is_ok());
+    assert_eq!(result.errors.len(), 2);
+    assert_eq!(result.errors[0].error, OperationError::StackUnderflow);
+    let positions: Vec<(usize, usize)> = result
+        .errors
+        .iter()
+        .filter_map(|error| error.position)
+        .map(|position| (position.line, position.column))
+        .collect();
+    assert_eq!(positions, vec![(2, 3), (2, 5)]);
+    assert!(result.defined_words.is_empty());
+}
+
+#[derive(Clone, Default)]
+struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+
+impl std::io::Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stack_persistence_to_writer() {
+    let saved = SharedWriter::default();
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::writer(saved.clone()));
+
+    calc.run("1 2 3 +".to_string());
+    assert_eq!(
+        String::from_utf8(saved.0.borrow().clone()).unwrap(),
+        "1 5\n"
+    );
+}
+
+#[test]
+fn test_stack_persistence_to_writer_saves_every_evaluation_on_its_own_line() {
+    let saved = SharedWriter::default();
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::writer(saved.clone()));
+
+    calc.run("1 2".to_string());
+    calc.run("3".to_string());
+    let saved = String::from_utf8(saved.0.borrow().clone()).unwrap();
+    assert_eq!(saved, "1 2\n1 2 3\n");
+    let last = saved.lines().last().unwrap();
+    assert_eq!(file_manager::parse_stack(last), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn test_stack_persistence_to_file() {
+    let path = std::env::temp_dir().join(format!("forth-stack-{}.fth", std::process::id()));
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::file(&path));
+
+    calc.run("10 -2".to_string());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "10 -2");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_stack_persistence_failure_is_reported() {
+    let path = std::env::temp_dir()
+        .join("forth-missing-dir")
+        .join("stack.fth");
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::file(path));
+
+    let result = calc.evaluate("1");
+    assert_eq!(result.errors[0].error, OperationError::FailWritingFile);
+}
+
+#[test]
+fn test_with_stack_seeds_the_stack() {
+    let mut calc = ForthCalculator::with_stack(DEFAULT_STACK_SIZE, vec![2, 3]).unwrap();
+    calc.run("*".to_string());
+    assert_eq!(calc.get_stack(), &vec![6]);
+
+    assert_eq!(
+        ForthCalculator::with_stack(2, vec![1, 2, 3]).err(),
+        Some(OperationError::StackOverflow)
+    );
+}
+
+#[test]
+fn test_from_stack_file_chains_runs() {
+    let path = std::env::temp_dir().join(format!("forth-chain-{}.fth", std::process::id()));
+    let mut first = create_calculator(DEFAULT_STACK_SIZE);
+    first.set_stack_persistence(StackPersistence::file(&path));
+    first.run("1 2 3".to_string());
+
+    let mut second = ForthCalculator::from_stack_file(DEFAULT_STACK_SIZE, &path).unwrap();
+    second.run("+ +".to_string());
+    assert_eq!(second.get_stack(), &vec![6]);
+
+    assert_eq!(
+        ForthCalculator::from_stack_file(2, &path).err(),
+        Some(OperationError::StackOverflow)
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_from_stack_file_rejects_invalid_files() {
+    let path = std::env::temp_dir().join(format!("forth-invalid-{}.fth", std::process::id()));
+    std::fs::write(&path, "1 two 3").unwrap();
+    assert_eq!(
+        ForthCalculator::from_stack_file(DEFAULT_STACK_SIZE, &path).err(),
+        Some(OperationError::InvalidStackFile)
+    );
+    std::fs::write(&path, "1 99999").unwrap();
+    assert_eq!(
+        ForthCalculator::from_stack_file(DEFAULT_STACK_SIZE, &path).err(),
+        Some(OperationError::InvalidStackFile)
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        ForthCalculator::from_stack_file(DEFAULT_STACK_SIZE, &path).err(),
+        Some(OperationError::FailReadFile)
+    );
+}
+
+#[test]
+fn test_image_restores_dictionary_stack_and_settings() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+    calc.run(": foo 5 ; : bar foo ; : foo 6 ; 1 2".to_string());
+
+    let path = std::env::temp_dir().join(format!("forth-image-{}.img", std::process::id()));
+    calc.save_image(&path).unwrap();
+    let mut restored = ForthCalculator::load_image(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    restored.run("bar foo +".to_string());
+    assert_eq!(restored.get_stack(), &vec![1, 2, 11]);
+
+    restored.run("drop drop drop drop".to_string());
+    assert_eq!(
+        restored.get_output(),
+        "stack-underflow: 'drop' at <input>:1:16\n"
+    );
+}
+
+#[test]
+fn test_load_image_rejects_other_files() {
+    let path = std::env::temp_dir().join(format!("forth-not-image-{}.fth", std::process::id()));
+    std::fs::write(&path, "1 2 3").unwrap();
+    assert_eq!(
+        ForthCalculator::load_image(&path).err(),
+        Some(OperationError::InvalidImage)
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_feed_keeps_unfinished_definitions_pending() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+
+    let result = calc.feed("1 : square\n");
+    assert_eq!(result.stack, vec![1]);
+    assert!(calc.has_pending_input());
+
+    let result = calc.feed("dup * ;\n3 square\n");
+    assert!(result.is_ok());
+    assert_eq!(result.defined_words, vec!["square"]);
+    assert_eq!(result.stack, vec![1, 9]);
+    assert!(!calc.has_pending_input());
+}
+
+#[test]
+fn test_feed_keeps_unfinished_literals_and_words_pending() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+
+    assert_eq!(calc.feed(".\" hello ").output, "");
+    assert_eq!(calc.feed("world\" 1").output, " hello world");
+    assert_eq!(calc.get_stack(), &vec![]);
+
+    calc.feed("2 du");
+    let result = calc.feed("p\n");
+    assert_eq!(result.stack, vec![12, 12]);
+}
+
+#[test]
+fn test_feed_keeps_top_level_conditionals_pending() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    assert_eq!(calc.feed("1 if\n").output, "");
+    assert!(calc.has_pending_input());
+    let result = calc.feed(".\" yes\" else 2 then 3\n");
+    assert!(!calc.has_pending_input());
+    assert!(result.is_ok());
+    assert_eq!(result.output, " yes");
+    assert_eq!(result.stack, vec![3]);
+}
+
+#[test]
+fn test_top_level_conditionals() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("0 if 1 else 2 then 5 1 if 3 1 if 4 then then");
+    assert!(result.is_ok());
+    assert_eq!(result.stack, vec![2, 5, 3, 4]);
+
+    let result = calc.evaluate("1 if s\" 6\" evaluate then");
+    assert!(result.is_ok());
+    assert_eq!(result.stack, vec![2, 5, 3, 4, 6]);
+}
+
+#[test]
+fn test_errors_in_top_level_conditionals() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_recovery_policy(RecoveryPolicy::Continue);
+    calc.set_error_format(ErrorFormat::Detailed);
+    let result = calc.evaluate("if 1 then 1 if drop drop then 7 1 if 2");
+    assert_eq!(result.stack, vec![7]);
+    let messages: Vec<String> = result.errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "stack-underflow: 'if' at <input>:1:1\n",
+            "stack-underflow: 'drop' at <input>:1:13\n",
+            "invalid-word: 'if' at <input>:1:35\n",
+        ]
+    );
+}
+
+#[test]
+fn test_feed_counts_positions_across_chunks() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+
+    calc.feed("1 2 +\n");
+    calc.feed(": foo\n");
+    let result = calc.feed("drop drop ; foo\n");
+    assert_eq!(
+        result.errors[0].to_string(),
+        "stack-underflow: 'drop' in 'foo' at <input>:3:13\n"
+    );
+}
+
+#[test]
+fn test_finish_reports_unfinished_constructs() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.feed("5 : foo 1");
+    let result = calc.finish();
+    assert_eq!(result.errors[0].error, OperationError::InvalidWordFormat);
+    assert!(!calc.has_pending_input());
+}
+
+#[test]
+fn test_trace_output_shows_every_token() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_trace_output(OutputSink::default());
+
+    calc.run(": square dup * ; 3 square .".to_string());
+    assert_eq!(
+        calc.get_trace_output(),
+        "3 (  )\nsquare ( 3 )\n  dup ( 3 )\n  * ( 3 3 )\n. ( 9 )\n"
+    );
+    assert_eq!(calc.get_output(), "9 ");
+}
+
+#[test]
+fn test_bye_stops_execution_and_saves_the_stack() {
+    let saved = SharedWriter::default();
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::writer(saved.clone()));
+
+    let result = calc.evaluate("1 2 . bye 3 .");
+    assert!(result.is_ok());
+    assert_eq!(result.halted, Some(Halt::Bye));
+    assert_eq!(result.output, "2 ");
+    assert_eq!(result.stack, vec![1]);
+    assert_eq!(String::from_utf8(saved.0.borrow().clone()).unwrap(), "1\n");
+
+    let result = calc.evaluate("5");
+    assert!(!result.is_halted());
+    assert_eq!(result.stack, vec![1, 5]);
+}
+
+#[test]
+fn test_exit_code_inside_words_stops_every_caller() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(": check 0 = if 7 exit-code then 1 ; : run check 2 ; 0 run 3");
+    assert_eq!(result.halted, Some(Halt::ExitCode(7)));
+    assert!(result.stack.is_empty());
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_shebang_line_is_skipped() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+
+    let result = calc.evaluate("#!/usr/bin/env rust-the-forth\n1 2 + . +");
+    assert!(result.output.starts_with("3 "));
+    assert_eq!(
+        result.errors[0].to_string(),
+        "stack-underflow: '+' at <input>:2:9\n"
+    );
+
+    let result = calc.evaluate("1 #!");
+    assert_eq!(result.errors[0].error, OperationError::WordNotFound);
+}
+
+#[test]
+fn test_args_are_available_through_argc_and_arg() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_args(&["script.fth", "hello", "42"]).unwrap();
+
+    let result = calc.evaluate("argc 1 arg 7 arg");
+    assert_eq!(result.stack[0], 3);
+    assert_eq!(result.stack[3..], [0, 0]);
+    let (address, len) = (result.stack[1], result.stack[2]);
+    assert_eq!(
+        calc.get_data_space().read_string(address, len),
+        Ok("hello".to_string())
+    );
+}
+
+#[test]
+fn test_next_arg_and_argv_read_the_program_arguments() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_args(&["script.fth", "first", "second"]).unwrap();
+
+    let result = calc.evaluate("next-arg 2 argv next-arg next-arg");
+    let strings: Vec<String> = result.stack[..6]
+        .chunks(2)
+        .map(|arg| calc.get_data_space().read_string(arg[0], arg[1]).unwrap())
+        .collect();
+    assert_eq!(strings, vec!["first", "second", "second"]);
+    assert_eq!(result.stack[6..], [0, 0]);
+}
+
+fn create_source_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("forth-{}-{}", name, std::process::id()));
+    for (path, content) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_include_resolves_paths_relative_to_the_including_file() {
+    let dir = create_source_dir(
+        "include",
+        &[
+            ("main.fth", "include lib/square.fth 3 square ."),
+            ("lib/square.fth", "include helpers.fth : square dup mul ;"),
+            ("lib/helpers.fth", ": mul * ;"),
+        ],
+    );
+    let main = dir.join("main.fth");
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_source_name(main.to_str().unwrap());
+
+    let result = calc.evaluate(&std::fs::read_to_string(&main).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.output, "9 ");
+    assert_eq!(result.defined_words, vec!["mul", "square"]);
+}
+
+#[test]
+fn test_include_accepts_dot_relative_paths() {
+    let dir = create_source_dir(
+        "include-dot",
+        &[
+            ("main.fth", "include ./lib/square.fth 3 square . .\" done\""),
+            (
+                "lib/square.fth",
+                "REQUIRE ../helpers.fth : square dup mul ;",
+            ),
+            ("helpers.fth", ": mul * ;"),
+        ],
+    );
+    let main = dir.join("main.fth");
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_source_name(main.to_str().unwrap());
+
+    let result = calc.evaluate(&std::fs::read_to_string(&main).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.output, "9  done");
+}
+
+#[test]
+fn test_require_loads_a_file_once() {
+    let dir = create_source_dir("require", &[("hello.fth", ".\" hello\"")]);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_source_name(dir.join("main.fth").to_str().unwrap());
+
+    let result = calc.evaluate("require hello.fth REQUIRE hello.fth include hello.fth");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.output, " hello hello");
+}
+
+#[test]
+fn test_include_cycles_are_detected() {
+    let dir = create_source_dir(
+        "cycle",
+        &[("a.fth", "1 include b.fth"), ("b.fth", "2 include a.fth")],
+    );
+    let a = dir.join("a.fth");
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+    calc.set_source_name(a.to_str().unwrap());
+
+    let result = calc.evaluate(&std::fs::read_to_string(&a).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(result.stack, vec![1, 2]);
+    assert_eq!(
+        result.errors[0].to_string(),
+        format!(
+            "include-cycle: 'a.fth' at {}:1:11\n",
+            dir.join("b.fth").display()
+        )
+    );
+}
+
+#[test]
+fn test_errors_in_included_files_name_the_file_and_line() {
+    let dir = create_source_dir("include-error", &[("lib.fth", "1 2\n+ +\n+")]);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+    calc.set_recovery_policy(RecoveryPolicy::Halt);
+    calc.set_source_name(dir.join("main.fth").to_str().unwrap());
+
+    let result = calc.evaluate("include lib.fth 5");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(
+        result.errors[0].to_string(),
+        format!(
+            "stack-underflow: '+' at {}:2:3\n",
+            dir.join("lib.fth").display()
+        )
+    );
+    assert!(result.stack.is_empty());
+}
+
+#[test]
+fn test_include_reports_missing_files() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+
+    let result = calc.evaluate("include missing-file.fth 1");
+    assert_eq!(
+        result.errors[0].to_string(),
+        "We have a problem with reading the file: 'missing-file.fth' at <input>:1:9\n"
+    );
+    assert_eq!(result.stack, vec![1]);
+
+    let result = calc.evaluate("include");
+    assert_eq!(result.errors[0].error, OperationError::MissingFileName);
+}
+
+#[test]
+fn test_included_takes_the_path_from_the_stack() {
+    let dir = create_source_dir("included", &[("lib.fth", ": answer 42 ;")]);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_args(&["script.fth", dir.join("lib.fth").to_str().unwrap()])
+        .unwrap();
+
+    let result = calc.evaluate("1 arg included answer");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.stack, vec![42]);
+}
+
+#[test]
+fn test_feed_waits_for_the_included_file_name() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.feed("1 include\n");
+    assert!(calc.has_pending_input());
+    assert_eq!(calc.get_stack(), &vec![1]);
+}
+
+#[test]
+fn test_include_falls_back_to_the_search_path() {
+    let dir = create_source_dir(
+        "search-path",
+        &[
+            ("first/common.fth", ": greet .\" first\" ;"),
+            ("second/common.fth", ": greet .\" second\" ;"),
+            ("second/extra.fth", ": extra 7 ;"),
+        ],
+    );
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_search_path(&[dir.join("first"), dir.join("second")]);
+
+    let prelude = calc.include("common.fth");
+    let result = calc.evaluate("require common.fth include extra.fth greet extra");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(prelude.is_ok());
+    assert!(result.is_ok());
+    assert_eq!(result.output, " first");
+    assert_eq!(result.stack, vec![7]);
+}
+
+#[test]
+fn test_evaluate_runs_a_string_in_the_current_context() {
+    let saved = SharedWriter::default();
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_stack_persistence(StackPersistence::writer(saved.clone()));
+    calc.set_args(&["script.fth", ": square dup * ; 3 square .\" evaluated\""])
+        .unwrap();
+
+    let result = calc.evaluate(".\" start\" 1 arg evaluate . 2 square");
+    assert!(result.is_ok());
+    assert_eq!(result.output, " start evaluated9 ");
+    assert_eq!(result.stack, vec![4]);
+    assert_eq!(result.defined_words, vec!["square"]);
+    assert_eq!(String::from_utf8(saved.0.borrow().clone()).unwrap(), "4\n");
+}
+
+#[test]
+fn test_evaluate_inside_words_reports_the_failing_token() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_error_format(ErrorFormat::Detailed);
+    calc.set_args(&["script.fth", "1 +", "1 foo"]).unwrap();
+
+    let result = calc.evaluate(": run arg evaluate ; 5 1 run 2 run");
+    assert_eq!(result.stack, vec![6, 1]);
+    assert_eq!(
+        result.errors[0].to_string(),
+        "?: 'foo' in 'run' at <input> (evaluate):1:3\n"
+    );
+
+    let result = calc.evaluate("drop drop : ev evaluate ; 7 ev");
+    assert!(result.stack.is_empty());
+    assert_eq!(
+        result.errors[0].to_string(),
+        "stack-underflow: 'evaluate' in 'ev' at <input>:1:29\n"
+    );
+}
+
+#[test]
+fn test_evaluate_follows_the_recovery_policy() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_recovery_policy(RecoveryPolicy::Continue);
+    let result = calc.evaluate("s\" 1 + 2\" evaluate 3");
+    assert_eq!(result.stack, vec![2, 3]);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].source, "<input> (evaluate)");
+    assert_eq!(result.errors[0].position.map(|p| p.column), Some(3));
+
+    calc.set_recovery_policy(RecoveryPolicy::Halt);
+    let result = calc.evaluate("drop drop s\" 1 + 2\" evaluate 3");
+    assert!(result.stack.is_empty());
+    assert_eq!(result.output, "stack-underflow\n");
+}
+
+#[test]
+fn test_evaluate_can_include_files() {
+    let dir = create_source_dir("evaluate-include", &[("lib.fth", ": answer 42 ;")]);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_source_name(dir.join("main.fth").to_str().unwrap());
+
+    let result = calc.evaluate(": load s\" include lib.fth answer\" evaluate ; load 1 +");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(result.stack, vec![43]);
+    assert_eq!(result.defined_words, vec!["load", "answer"]);
+}
+
+#[test]
+fn test_string_literals_keep_their_case() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(".\" Hello\" S\" World\" TYPE");
+    assert_eq!(result.output, " HelloWorld");
+    assert!(result.stack.is_empty());
+}
+
+#[test]
+fn test_escaped_string_literals() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("s\\\" say \\\"hi\\\"\\tnow\\n\\x41\" type");
+    assert!(result.is_ok());
+    assert_eq!(result.output, "say \"hi\"\tnow\nA");
+}
+
+#[test]
+fn test_strings_in_words_are_stored_once() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(": greet s\" Hi \" type ; greet greet");
+    assert_eq!(result.output, "Hi Hi ");
+    assert_eq!(calc.get_data_space().as_bytes(), b"Hi ");
+}
+
+#[test]
+fn test_strings_can_be_modified() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(
+        "s\" hello\" over 72 swap c! over 1 + 2 46 fill type \
+         s\" ab\" drop s\" --\" drop 2 cmove 5 c@ 6 c@",
+    );
+    assert!(result.is_ok());
+    assert_eq!(result.output, "H..lo");
+    assert_eq!(result.stack, vec![97, 98]);
+    assert_eq!(calc.get_data_space().as_bytes(), b"H..loabab");
+}
+
+#[test]
+fn test_count_reads_counted_strings() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("s\\\" \\x03abc\" drop count type");
+    assert_eq!(result.output, "abc");
+}
+
+#[test]
+fn test_image_keeps_the_required_files() {
+    let dir = create_source_dir("image-require", &[("lib.fth", ": greet .\" hi\" ;")]);
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_source_name(dir.join("main.fth").to_str().unwrap());
+    calc.run("require lib.fth".to_string());
+
+    let mut restored = ForthCalculator::from_image(calc.image());
+    let result = restored.evaluate("require lib.fth greet");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_ok());
+    assert!(result.defined_words.is_empty());
+    assert_eq!(result.output, " hi");
+}
+
+#[test]
+fn test_image_keeps_string_literals() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.run(": greet s\" Hello\" type ;".to_string());
+
+    let mut restored = ForthCalculator::from_image(calc.image());
+    restored.run("greet".to_string());
+    assert_eq!(restored.get_output(), "Hello");
+}
+
+#[test]
+fn test_string_wordset() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(
+        ": name s\" Forth   \" -trailing ; \
+         s\" Hello, \" name s+ type \
+         s\" abc\" s\" abd\" compare \
+         s\" key=value\" s\" =\" search drop 1 /string type",
+    );
+    assert!(result.is_ok());
+    assert_eq!(result.output, "Hello, Forthvalue");
+    assert_eq!(result.stack, vec![-1]);
+}
+
+#[test]
+fn test_blank_clears_a_string() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("s\" abc\" over over blank s\"    \" compare");
+    assert_eq!(result.stack, vec![0]);
+    assert_eq!(calc.get_data_space().as_bytes(), b"      ");
+}
+
+#[test]
+fn test_print_literal_escapes() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(".\" a \\\"quoted\\\" word\\n\\ttab \\\\ done\" 1");
+    assert!(result.is_ok());
+    assert_eq!(result.output, " a \"quoted\" word\n\ttab \\ done");
+    assert_eq!(result.stack, vec![1]);
+}
+
+#[test]
+fn test_print_literal_after_any_whitespace() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate(".\"\thi\" .\"\nthere\"");
+    assert!(result.is_ok());
+    assert_eq!(result.output, "\thi\nthere");
+}
+
+#[test]
+fn test_paren_literal_prints_immediately() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    let result = calc.evaluate("1 . .( Hello) : foo .( Defining foo) 2 ; cr foo .");
+    assert!(result.is_ok());
+    assert_eq!(result.output, "1 HelloDefining foo\n2 ");
+
+    let result = calc.evaluate("1 . : bar .( def) ; 2 .");
+    assert_eq!(result.output, "1 def2 ");
+
+    let result = calc.evaluate(": baz .( side) ; : broken");
+    assert_eq!(result.output, "? invalid word format\n");
+}
+
+#[test]
+fn test_unterminated_literals_are_errors() {
+    let cases = [
+        ("1 .\" hello", 3),
+        ("1 .\" hello\\\"", 3),
+        (": foo s\" abc ;", 7),
+        ("1 .( hello", 3),
+    ];
+    for (code, column) in cases {
+        let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+        let result = calc.evaluate(code);
+        assert_eq!(result.errors[0].error, OperationError::UnterminatedLiteral);
+        assert_eq!(result.errors[0].position.map(|p| p.column), Some(column));
+    }
+}
+
+#[test]
+fn test_feed_keeps_unterminated_literals_pending() {
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    assert_eq!(calc.feed(".\" a\\\" ").output, "");
+    assert_eq!(calc.feed("b\" .( c").output, " a\" b");
+    assert!(calc.has_pending_input());
+
+    let result = calc.finish();
+    assert_eq!(result.errors[0].error, OperationError::UnterminatedLiteral);
+}
+
+#[test]
+fn test_short_errors_name_the_included_file_and_line() {
+    let dir = create_source_dir(
+        "include-short-error",
+        &[
+            ("lib.fth", "1 2\n+ +\n+"),
+            ("a.fth", "include b.fth"),
+            ("b.fth", "\ninclude a.fth"),
+        ],
+    );
+    let mut calc = create_calculator(DEFAULT_STACK_SIZE);
+    calc.set_recovery_policy(RecoveryPolicy::Halt);
+    calc.set_source_name(dir.join("main.fth").to_str().unwrap());
+
+    let underflow = calc.evaluate("include lib.fth 5");
+    let missing = calc.evaluate("1\ninclude missing.fth");
+    let cycle = calc.evaluate("include a.fth");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        underflow.output,
+        format!("{}:2: stack-underflow\n", dir.join("lib.fth").display())
+    );
+    assert_eq!(
+        missing.output,
+        format!(
+            "{}:2: missing.fth: We have a problem with reading the file\n",
+            dir.join("main.fth").display()
+        )
+    );
+    assert_eq!(
+        cycle.output,
+        format!("{}:2: a.fth: include-cycle\n", dir.join("b.fth").display())
+    );
+
+    let result = calc.evaluate("1 2 +");
+    assert!(result.is_ok());
+    assert_eq!(calc.evaluate("+ +").output, "stack-underflow\n");
+}
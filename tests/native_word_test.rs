@@ -0,0 +1,88 @@
+use rust_the_forth::core::error::OperationError;
+use rust_the_forth::core::execution_context::ExecutionContext;
+use rust_the_forth::core::forth_calculator::ForthCalculator;
+use rust_the_forth::core::operation::Operation;
+
+const DEFAULT_STACK_SIZE: i16 = 128;
+
+struct Square;
+
+impl Operation for Square {
+    fn apply(&self, context: &mut ExecutionContext) -> Result<(), OperationError> {
+        let value = context.pop()?;
+        context.push(value * value)
+    }
+}
+
+fn double(context: &mut ExecutionContext) -> Result<(), OperationError> {
+    let value = context.pop()?;
+    context.push(value * 2)
+}
+
+#[test]
+fn test_register_native_closure_and_struct() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.register_native("double", double);
+    calculator.register_native("square", Square);
+    calculator.register_native("hello", |context: &mut ExecutionContext| {
+        context.output.write_str("hello ")
+    });
+
+    calculator.run("3 double square hello".to_string());
+    assert_eq!(calculator.get_stack(), &vec![36]);
+    assert_eq!(calculator.get_output(), "hello ");
+}
+
+#[test]
+fn test_native_words_are_case_insensitive() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.register_native("Double", double);
+
+    calculator.run("1 DOUBLE double Double".to_string());
+    assert_eq!(calculator.get_stack(), &vec![8]);
+}
+
+#[test]
+fn test_native_words_shadow_previous_definitions() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.run(": twice dup + ; : old-dup dup ;".to_string());
+    calculator.register_native("dup", double);
+    calculator.register_native("twice", Square);
+
+    calculator.run("3 dup 5 twice 7 old-dup".to_string());
+    assert_eq!(calculator.get_stack(), &vec![6, 25, 7, 7]);
+}
+
+#[test]
+fn test_user_words_can_shadow_and_call_native_words() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.register_native("double", double);
+
+    calculator.run(": quad double double ; 1 quad : double 0 ; 1 double 1 quad".to_string());
+    assert_eq!(calculator.get_stack(), &vec![4, 1, 0, 4]);
+}
+
+#[test]
+fn test_native_word_errors_are_reported() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.register_native("double", double);
+
+    calculator.run("double".to_string());
+    assert_eq!(calculator.get_output(), "stack-underflow\n");
+}
+
+#[test]
+fn test_image_keeps_native_words_until_rebound() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.register_native("double", double);
+    calculator.run(": quad double double ;".to_string());
+
+    let mut restored = ForthCalculator::from_image(calculator.image());
+    restored.run("1 quad".to_string());
+    assert_eq!(restored.get_output(), "?\n");
+
+    restored.register_native("double", double);
+    restored.run("quad".to_string());
+    assert_eq!(restored.get_stack(), &vec![4]);
+    assert_eq!(restored.image().words, calculator.image().words);
+}
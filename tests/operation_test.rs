@@ -1,58 +1,72 @@
 use rust_the_forth::core::error::OperationError;
+use rust_the_forth::core::execution_context::ExecutionContext;
 use rust_the_forth::core::operation::get_all_standar_operations;
 use rust_the_forth::core::operation::OperationType;
 
 #[test]
 fn test_complex_sequence() {
     let ops = get_all_standar_operations();
-    let mut stack = vec![10, 5, 3, 4, 2];
+    let mut context = ExecutionContext::with_stack(vec![10, 5, 3, 4, 2]);
 
-    ops[&OperationType::Mul].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, 5, 3, 8]);
+    ops[&OperationType::Mul].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 5, 3, 8]);
 
-    ops[&OperationType::Sub].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, 5, -5]);
+    ops[&OperationType::Sub].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 5, -5]);
 
-    ops[&OperationType::Div].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, -1]);
+    ops[&OperationType::Div].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, -1]);
 
-    ops[&OperationType::Sub].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![11]);
+    ops[&OperationType::Sub].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![11]);
 }
 
 #[test]
 fn test_error_handling() {
     let ops = get_all_standar_operations();
-    let mut stack = vec![1, 0];
+    let mut context = ExecutionContext::with_stack(vec![1, 0]);
 
     assert!(matches!(
-        ops[&OperationType::Div].apply(&mut stack),
+        ops[&OperationType::Div].apply(&mut context),
         Err(OperationError::DivisionByZero)
     ));
 
-    assert_eq!(stack, vec![]);
+    assert_eq!(context.stack, vec![]);
 }
 
 #[test]
 fn test_complex_sequence_underflow() {
     let ops = get_all_standar_operations();
 
-    let mut stack = vec![10, 5, 3, 4, 2];
+    let mut context = ExecutionContext::with_stack(vec![10, 5, 3, 4, 2]);
 
-    ops[&OperationType::Mul].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, 5, 3, 8]);
+    ops[&OperationType::Mul].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 5, 3, 8]);
 
-    ops[&OperationType::Sub].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, 5, -5]);
+    ops[&OperationType::Sub].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, 5, -5]);
 
-    ops[&OperationType::Div].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![10, -1]);
+    ops[&OperationType::Div].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![10, -1]);
 
-    ops[&OperationType::Sub].apply(&mut stack).unwrap();
-    assert_eq!(stack, vec![11]);
+    ops[&OperationType::Sub].apply(&mut context).unwrap();
+    assert_eq!(context.stack, vec![11]);
 
     assert!(matches!(
-        ops[&OperationType::Sub].apply(&mut stack),
+        ops[&OperationType::Sub].apply(&mut context),
         Err(OperationError::StackUnderflow)
     ));
 }
+
+#[test]
+fn test_output_operations_share_the_standard_map() {
+    let ops = get_all_standar_operations();
+    let mut context = ExecutionContext::with_stack(vec![65, 42]);
+
+    ops[&OperationType::Dot].apply(&mut context).unwrap();
+    ops[&OperationType::Cr].apply(&mut context).unwrap();
+    ops[&OperationType::Emit].apply(&mut context).unwrap();
+
+    assert_eq!(context.output.as_str(), "42 \nA ");
+    assert!(context.stack.is_empty());
+}
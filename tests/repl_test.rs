@@ -0,0 +1,91 @@
+use rust_the_forth::core::error::{OperationError, RecoveryPolicy};
+use rust_the_forth::core::forth_calculator::ForthCalculator;
+use rust_the_forth::core::run_result::Halt;
+use rust_the_forth::utils::repl::Repl;
+
+const DEFAULT_STACK_SIZE: i16 = 128;
+
+fn run_repl(input: &str) -> (String, Repl) {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.set_recovery_policy(RecoveryPolicy::Halt);
+    run_repl_with(calculator, input)
+}
+
+fn run_repl_with(calculator: ForthCalculator, input: &str) -> (String, Repl) {
+    let mut repl = Repl::new(calculator);
+    let mut output = Vec::new();
+    repl.run(input.as_bytes(), &mut output).unwrap();
+    (String::from_utf8(output).unwrap(), repl)
+}
+
+#[test]
+fn test_repl_answers_ok_after_each_line() {
+    let (output, repl) = run_repl("1 2\n+ .\n3\n");
+    assert_eq!(output, " ok\n3  ok\n ok\n");
+    assert_eq!(repl.calculator().get_stack(), &vec![3]);
+}
+
+#[test]
+fn test_repl_reports_errors_and_keeps_going() {
+    let (output, repl) = run_repl("1 . + 5\nfoo\n2\n");
+    assert_eq!(output, "1 stack-underflow\n?\n ok\n");
+    assert_eq!(repl.calculator().get_stack(), &vec![2]);
+    let errors: Vec<OperationError> = repl.errors().iter().map(|e| e.error.clone()).collect();
+    assert_eq!(
+        errors,
+        vec![OperationError::StackUnderflow, OperationError::WordNotFound]
+    );
+}
+
+#[test]
+fn test_repl_supports_multi_line_definitions() {
+    let (output, _) = run_repl(": square\ndup\n* ;\n4 square .\n");
+    assert_eq!(output, " compiled\n compiled\n ok\n16  ok\n");
+}
+
+#[test]
+fn test_repl_eval_line() {
+    let mut repl = Repl::new(ForthCalculator::new(DEFAULT_STACK_SIZE));
+    assert_eq!(repl.eval_line(".\" hi\" cr"), " hi\n ok\n");
+    assert_eq!(repl.eval_line("drop"), "stack-underflow\n");
+}
+
+#[test]
+fn test_repl_stops_at_bye() {
+    let (output, repl) = run_repl("1 .\n5 exit-code 2\n3 .\n");
+    assert_eq!(output, "1  ok\n");
+    assert_eq!(repl.halted(), Some(Halt::ExitCode(5)));
+    assert!(repl.calculator().get_stack().is_empty());
+}
+
+#[test]
+fn test_repl_keeps_the_recovery_policy() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.set_recovery_policy(RecoveryPolicy::Continue);
+    let (output, repl) = run_repl_with(calculator, "1 . + 5\n");
+    assert_eq!(output, "1 stack-underflow\n");
+    assert_eq!(repl.calculator().get_stack(), &vec![5]);
+}
+
+#[test]
+fn test_repl_rolls_back_failing_lines_in_transactional_mode() {
+    let mut calculator = ForthCalculator::new(DEFAULT_STACK_SIZE);
+    calculator.set_transactional(true);
+    let (output, repl) = run_repl_with(calculator, "1 2\n3 : foo ; drop drop drop drop\nfoo\n");
+    assert_eq!(output, " ok\nstack-underflow\n?\n");
+    assert_eq!(repl.calculator().get_stack(), &vec![1, 2]);
+}
+
+#[test]
+fn test_repl_runs_multi_line_conditionals() {
+    let (output, repl) = run_repl("0\nif 1\nelse 2 then\n");
+    assert_eq!(output, " ok\n compiled\n ok\n");
+    assert_eq!(repl.calculator().get_stack(), &vec![2]);
+}
+
+#[test]
+fn test_repl_reports_unfinished_input_at_the_end() {
+    let (output, repl) = run_repl("1\n: foo 2\n");
+    assert_eq!(output, " ok\n compiled\n? invalid word format\n");
+    assert!(!repl.calculator().has_pending_input());
+}